@@ -1,48 +1,480 @@
-use std::{
-    env,
-    fs::File,
-    io::{self, BufRead},
-    path::Path,
+use crate::logging::{self, Level};
+use bananagrams::{
+    Area, FewestWords, Lexicographic, LongestWord, MostSquare, MostWords, MoveGenerator, Objective,
+    ParetoMeasure, Perimeter, ReplacementPolicy, SolveOptions, WordOrder,
 };
+use clap::{error::ErrorKind, ArgAction, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use std::{env, ffi::OsString, path::PathBuf, sync::Arc, time::Duration};
 
-//if the cmd line arg at index is parseable as a T, return that
-//else return the default value
-pub fn getarg<T: std::str::FromStr>(index: usize, default: T) -> T {
-    match env::args().nth(index) {
-        Some(arg) => arg.parse().unwrap_or(default),
-        None => default,
+#[derive(Debug, Parser)]
+#[command(
+    name = "bananagrams",
+    version,
+    about = "Find the smallest bananagrams board that uses every tile",
+    after_help = "Ex: bananagrams solve loremipsum -c -s -f common.txt"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+    #[arg(
+        short = 'q',
+        long,
+        global = true,
+        conflicts_with = "verbose",
+        help = "Only print results and errors"
+    )]
+    pub quiet: bool,
+    #[arg(
+        short = 'v',
+        long,
+        global = true,
+        action = ArgAction::Count,
+        help = "Log more to stderr: -v for each first word, -vv for every board searched"
+    )]
+    pub verbose: u8,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    #[command(about = "Find the smallest board using every tile (the default command)")]
+    Solve(SolveArgs),
+    #[command(about = "Check that a saved board is valid, uses exactly the tiles and is smallest")]
+    Verify(VerifyArgs),
+    #[command(about = "Time solving some hands several times over")]
+    Bench(BenchArgs),
+    #[command(about = "Draw a random hand from a full bag of tiles")]
+    Generate(GenerateArgs),
+    #[command(
+        about = "Play alone: solve a hand, then peel a tile and solve again until the bag is empty"
+    )]
+    Play(PlayArgs),
+    #[command(about = "Count every board a hand makes, to rate how hard it is")]
+    Count(CountArgs),
+}
+
+impl Cli {
+    pub fn parse_args() -> Cli {
+        Cli::parse_from(with_default_command(env::args_os().collect()))
+    }
+
+    pub fn level(&self) -> Level {
+        match (self.quiet, self.verbose) {
+            (true, _) => Level::Quiet,
+            (false, 0) => Level::Normal,
+            (false, 1) => Level::Verbose,
+            _ => Level::Trace,
+        }
+    }
+}
+
+//tiles given without a command still mean solve, as they always have, whatever flags
+//come before them; only flags, like --help, are left to the program itself
+fn with_default_command(mut args: Vec<OsString>) -> Vec<OsString> {
+    let command = Cli::command();
+    let mut given = args.iter().skip(1).filter_map(|a| a.to_str());
+    let has_command = given
+        .clone()
+        .any(|arg| arg == "help" || command.get_subcommands().any(|c| c.get_name() == arg));
+    if !has_command && given.any(|arg| !arg.starts_with('-')) {
+        args.insert(1, "solve".into());
+    }
+    args
+}
+
+//tiles given on the command line, as letters
+#[derive(Debug, Clone)]
+pub struct Tiles(pub Vec<char>);
+
+fn parse_tiles(text: &str) -> Result<Tiles, String> {
+    if text.is_empty() {
+        return Err("there has to be at least one tile".to_string());
+    }
+    match text.chars().find(|c| !c.is_ascii_alphabetic()) {
+        Some(c) => Err(format!("'{}' is not a letter", c)),
+        None => Ok(Tiles(text.to_ascii_lowercase().chars().collect())),
     }
 }
 
-//if this arg was supplied, return its index
-//else None
-pub fn arg_pos(arg: &str) -> Option<usize> {
-    for (i, argument) in env::args().enumerate() {
-        if argument == arg {
-            return Some(i);
+fn parse_seconds(text: &str) -> Result<Duration, String> {
+    text.parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("'{}' is not a number of seconds", text))
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum GeneratorArg {
+    Rows,
+    Anchored,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ReplacementArg {
+    Depth,
+    Lru,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ParetoArg {
+    LongestSide,
+    Perimeter,
+}
+
+impl From<ParetoArg> for ParetoMeasure {
+    fn from(arg: ParetoArg) -> ParetoMeasure {
+        match arg {
+            ParetoArg::LongestSide => ParetoMeasure::LongestSide,
+            ParetoArg::Perimeter => ParetoMeasure::Perimeter,
         }
     }
-    None
 }
 
-pub fn arg_exists(arg: &str) -> bool {
-    arg_pos(arg).is_some()
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ObjectiveArg {
+    Area,
+    Perimeter,
+    MostSquare,
+    FewestWords,
+    MostWords,
+    LongestWord,
 }
 
-pub fn after_flag_or<T: std::str::FromStr>(flag: &str, default: T) -> T {
-    match arg_pos(flag) {
-        Some(index) => getarg(index + 1, default),
-        None => default,
+impl ObjectiveArg {
+    fn objective(self) -> Arc<dyn Objective> {
+        match self {
+            ObjectiveArg::Area => Arc::new(Area),
+            ObjectiveArg::Perimeter => Arc::new(Perimeter),
+            ObjectiveArg::MostSquare => Arc::new(MostSquare),
+            ObjectiveArg::FewestWords => Arc::new(FewestWords),
+            ObjectiveArg::MostWords => Arc::new(MostWords),
+            ObjectiveArg::LongestWord => Arc::new(LongestWord),
+        }
     }
 }
 
-// The output is wrapped in a Result to allow matching on errors
-// Returns an Iterator to the Reader of the lines of the file.
-pub fn read_lines<P: AsRef<Path>>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>> {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    //the board as it is printed, with progress along the way
+    Text,
+    //a single object once the search is done, see output.rs
+    Json,
 }
 
-pub fn num_args() -> usize {
-    env::args().count()
+//the dictionary and how to search, shared by every command that solves
+#[derive(Debug, Args)]
+pub struct SearchArgs {
+    #[arg(
+        short = 'f',
+        long = "words",
+        default_value = "words.txt",
+        help = "File of words to draw from, one per line"
+    )]
+    pub words: PathBuf,
+    #[arg(
+        short = 's',
+        long,
+        conflicts_with = "longest_first",
+        help = "Try shorter words first"
+    )]
+    pub shortest_first: bool,
+    #[arg(short = 'l', long, help = "Try longer words first")]
+    pub longest_first: bool,
+    #[arg(short = 'c', long, help = "Check the board is valid at every step")]
+    pub check_every_step: bool,
+    #[arg(short = 'r', long, help = "Randomize the order words are chosen in")]
+    pub random: bool,
+    #[arg(long, default_value_t = 0, help = "Lower limit for word size")]
+    pub min_word_length: usize,
+    #[arg(long, help = "Upper limit for word size")]
+    pub max_word_length: Option<usize>,
+    #[arg(
+        long,
+        value_enum,
+        default_value = "anchored",
+        help = "How words are placed"
+    )]
+    pub generator: GeneratorArg,
+    #[arg(
+        long,
+        default_value_t = 64,
+        value_name = "MB",
+        help = "Megabytes kept for remembering searched boards"
+    )]
+    pub table_memory: usize,
+    #[arg(
+        long,
+        value_enum,
+        default_value = "depth",
+        help = "Which boards are forgotten first"
+    )]
+    pub replacement: ReplacementArg,
+    #[arg(
+        long,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u16).range(1..),
+        help = "Search with several threads at once",
+    )]
+    pub threads: u16,
+    #[arg(
+        long,
+        help = "Prune on a board's area alone instead of the area it must grow to"
+    )]
+    pub no_lower_bound: bool,
+    #[arg(
+        long,
+        help = "Search within a growing target area, so the first board found is smallest"
+    )]
+    pub iterative: bool,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = parse_seconds,
+        help = "Stop after this long and give the best board found so far"
+    )]
+    pub time_limit: Option<Duration>,
+    #[arg(
+        long,
+        value_name = "NODES",
+        help = "Stop after searching this many boards and give the best found so far"
+    )]
+    pub node_limit: Option<u64>,
+}
+
+impl SearchArgs {
+    pub fn options(&self) -> Result<SolveOptions, clap::Error> {
+        let max_word_length = self.max_word_length.unwrap_or(usize::MAX);
+        if self.min_word_length > max_word_length {
+            return Err(Cli::command().error(
+                ErrorKind::ArgumentConflict,
+                "--min-word-length can't be more than --max-word-length",
+            ));
+        }
+        Ok(SolveOptions {
+            preemptive_checking: self.check_every_step,
+            shuffle: self.random,
+            word_order: if self.longest_first {
+                WordOrder::LongestFirst
+            } else if self.shortest_first {
+                WordOrder::ShortestFirst
+            } else {
+                WordOrder::Dictionary
+            },
+            min_word_length: self.min_word_length,
+            max_word_length,
+            move_generator: match self.generator {
+                GeneratorArg::Rows => MoveGenerator::Rows,
+                GeneratorArg::Anchored => MoveGenerator::Anchored,
+            },
+            table_memory: self.table_memory << 20,
+            replacement: match self.replacement {
+                ReplacementArg::Depth => ReplacementPolicy::DepthPreferred,
+                ReplacementArg::Lru => ReplacementPolicy::LeastRecentlyUsed,
+            },
+            threads: self.threads as usize,
+            lower_bound: !self.no_lower_bound,
+            iterative_deepening: self.iterative,
+            visit_events: logging::enabled(Level::Trace),
+            timings: logging::enabled(Level::Verbose),
+            time_limit: self.time_limit,
+            node_limit: self.node_limit,
+            cancel: None,
+            checkpoint_interval: None,
+            all_optimal: false,
+            max_optimal_boards: SolveOptions::default().max_optimal_boards,
+            objective: None,
+            partial: false,
+        })
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct SolveArgs {
+    #[arg(value_parser = parse_tiles, help = "The tiles to use, as one word")]
+    pub tiles: Tiles,
+    #[arg(
+        long,
+        requires = "height",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Find any board fitting in a rectangle this wide instead of the smallest",
+    )]
+    pub width: Option<u64>,
+    #[arg(
+        long,
+        requires = "width",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Height of the rectangle to fit in",
+    )]
+    pub height: Option<u64>,
+    #[arg(
+        long,
+        value_enum,
+        default_value = "text",
+        help = "How to write the result"
+    )]
+    pub format: Format,
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "width",
+        help = "Save progress to this file now and then, and when stopped early"
+    )]
+    pub checkpoint: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = parse_seconds,
+        default_value = "60",
+        requires = "checkpoint",
+        help = "How often to save progress"
+    )]
+    pub checkpoint_interval: Duration,
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "width",
+        help = "Carry on from progress saved with --checkpoint"
+    )]
+    pub resume: Option<PathBuf>,
+    #[arg(
+        long,
+        conflicts_with_all = ["width", "checkpoint", "resume"],
+        help = "Find every smallest board, counting boards that are moved or transposed copies once"
+    )]
+    pub all_optimal: bool,
+    #[arg(
+        long,
+        default_value_t = 100,
+        requires = "all_optimal",
+        help = "How many of the smallest boards to print, the rest are only counted"
+    )]
+    pub max_boards: usize,
+    #[arg(
+        long,
+        value_enum,
+        value_name = "MEASURE",
+        num_args = 0..=1,
+        default_missing_value = "longest-side",
+        conflicts_with_all = ["width", "checkpoint", "resume", "all_optimal"],
+        help = "Find the boards no other beats on both area and longest side, or perimeter"
+    )]
+    pub pareto: Option<ParetoArg>,
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "area",
+        conflicts_with_all = ["width", "all_optimal", "pareto", "iterative"],
+        help = "What makes a board best; a list breaks ties with each objective in turn"
+    )]
+    pub objective: Vec<ObjectiveArg>,
+    #[arg(
+        long,
+        conflicts_with_all = ["width", "all_optimal", "pareto", "iterative"],
+        help = "If not every tile can be used, place as many as possible and list the rest"
+    )]
+    pub partial: bool,
+    #[command(flatten)]
+    pub search: SearchArgs,
+}
+
+impl SolveArgs {
+    //None for the area alone, which the solver handles faster
+    pub fn objective(&self) -> Option<Arc<dyn Objective>> {
+        match self.objective.as_slice() {
+            [ObjectiveArg::Area] => None,
+            [single] => Some(single.objective()),
+            list => Some(Arc::new(Lexicographic(
+                list.iter().map(|arg| arg.objective()).collect(),
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    #[arg(help = "File holding the board")]
+    pub board: PathBuf,
+    #[arg(value_parser = parse_tiles, help = "The tiles the board should use")]
+    pub tiles: Tiles,
+    #[command(flatten)]
+    pub search: SearchArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    #[arg(required = true, value_parser = parse_tiles, help = "The hands to solve")]
+    pub hands: Vec<Tiles>,
+    #[arg(
+        long,
+        default_value_t = 3,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "How many times to solve each hand",
+    )]
+    pub runs: u32,
+    #[command(flatten)]
+    pub search: SearchArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct GenerateArgs {
+    #[arg(
+        short = 'n',
+        long,
+        default_value_t = 21,
+        value_parser = clap::value_parser!(u8).range(1..=144),
+        help = "How many tiles to draw",
+    )]
+    pub count: u8,
+    #[arg(long, help = "Seed for drawing the same tiles every time")]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Args)]
+pub struct PlayArgs {
+    #[arg(
+        long,
+        default_value_t = 21,
+        value_parser = clap::value_parser!(u8).range(1..=144),
+        help = "How many tiles to start with",
+    )]
+    pub hand: u8,
+    #[arg(long, help = "Seed for drawing the same tiles every time")]
+    pub seed: Option<u64>,
+    #[command(flatten)]
+    pub search: SearchArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct CountArgs {
+    #[arg(value_parser = parse_tiles, help = "The tiles to use, as one word")]
+    pub tiles: Tiles,
+    #[arg(long, help = "Also count the boards of each area")]
+    pub by_area: bool,
+    #[command(flatten)]
+    pub search: SearchArgs,
+}
+
+#[test]
+fn solves_without_a_command() {
+    let parse = |args: &[&str]| {
+        let args = ["bananagrams"].iter().chain(args).map(OsString::from);
+        Cli::try_parse_from(with_default_command(args.collect()))
+    };
+    for args in [
+        &["loremipsum"][..],
+        &["-q", "loremipsum"],
+        &["-c", "loremipsum"],
+        &["-v", "-c", "loremipsum", "-s"],
+    ] {
+        let cli = parse(args).unwrap_or_else(|e| panic!("{:?}: {}", args, e));
+        assert!(matches!(cli.command, Command::Solve(_)));
+    }
+    assert!(matches!(
+        parse(&["count", "-q", "lorem"]).unwrap().command,
+        Command::Count(_)
+    ));
+    assert_eq!(
+        parse(&["--help"]).unwrap_err().kind(),
+        ErrorKind::DisplayHelp
+    );
 }
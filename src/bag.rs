@@ -0,0 +1,91 @@
+//the 144 tiles of a bananagrams set, shuffled, for drawing hands and peeling from
+use rand::{prelude::*, rngs::StdRng};
+
+const DISTRIBUTION: [(char, usize); 26] = [
+    ('a', 13),
+    ('b', 3),
+    ('c', 3),
+    ('d', 6),
+    ('e', 18),
+    ('f', 3),
+    ('g', 4),
+    ('h', 3),
+    ('i', 12),
+    ('j', 2),
+    ('k', 2),
+    ('l', 5),
+    ('m', 3),
+    ('n', 8),
+    ('o', 11),
+    ('p', 3),
+    ('q', 2),
+    ('r', 9),
+    ('s', 6),
+    ('t', 9),
+    ('u', 6),
+    ('v', 3),
+    ('w', 3),
+    ('x', 2),
+    ('y', 3),
+    ('z', 2),
+];
+
+#[derive(Debug, Clone)]
+pub struct Bag {
+    tiles: Vec<char>,
+}
+
+impl Bag {
+    pub fn new() -> Bag {
+        Bag::shuffled(&mut thread_rng())
+    }
+
+    //the same seed always gives the same order of tiles
+    pub fn seeded(seed: u64) -> Bag {
+        Bag::shuffled(&mut StdRng::seed_from_u64(seed))
+    }
+
+    fn shuffled<R: Rng>(rng: &mut R) -> Bag {
+        let mut tiles: Vec<char> = DISTRIBUTION
+            .iter()
+            .flat_map(|&(letter, count)| std::iter::repeat_n(letter, count))
+            .collect();
+        tiles.shuffle(rng);
+        Bag { tiles }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    //takes count tiles out, or all that are left if there aren't that many
+    pub fn draw(&mut self, count: usize) -> Vec<char> {
+        let rest = self.tiles.len().saturating_sub(count);
+        self.tiles.split_off(rest)
+    }
+}
+
+impl Default for Bag {
+    fn default() -> Self {
+        Bag::new()
+    }
+}
+
+#[test]
+fn bag_holds_a_full_set() {
+    let mut bag = Bag::seeded(7);
+    assert_eq!(bag.len(), 144);
+    let hand = bag.draw(21);
+    assert_eq!(hand.len(), 21);
+    assert_eq!(bag.len(), 123);
+    let mut tiles = bag.draw(200);
+    assert!(bag.is_empty());
+    tiles.extend(hand.iter());
+    assert_eq!(tiles.iter().filter(|&&t| t == 'e').count(), 18);
+    assert_eq!(tiles.iter().filter(|&&t| t == 'z').count(), 2);
+    assert_eq!(Bag::seeded(7).draw(21), hand);
+}
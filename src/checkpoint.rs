@@ -0,0 +1,93 @@
+//enough of a search's progress to carry on from where it stopped: the best board,
+//and which first words have been searched under completely. the table of searched
+//boards isn't kept, so a resumed search repeats some work but finds the same minimum
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub tiles: String,
+    //first words are numbered by their place in the solver's filtered and ordered
+    //word list, so resuming needs the very same list
+    pub word_count: usize,
+    pub words_hash: u64,
+    //the area iterative deepening was searching within, None for a plain search
+    pub target_area: Option<usize>,
+    //the best board so far, cropped, and the first word it was found under
+    pub best: Option<Vec<String>>,
+    pub best_first_word: usize,
+    pub completed_first_words: Vec<usize>,
+    //the objective's name, None for the area
+    #[serde(default)]
+    pub objective: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckpointError {
+    DifferentTiles { saved: String },
+    //the dictionary, word lengths or word order differ
+    DifferentWords,
+    //one of the runs used iterative deepening and the other didn't
+    DifferentSearch,
+    //searches for every optimal board can't be resumed
+    AllOptimal,
+    //None is the area
+    DifferentObjective { saved: Option<String> },
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckpointError::DifferentTiles { saved } => {
+                write!(f, "the checkpoint is for the tiles '{}'", saved)
+            }
+            CheckpointError::DifferentWords => write!(
+                f,
+                "the checkpoint was made with a different word list or word order"
+            ),
+            CheckpointError::DifferentSearch => write!(
+                f,
+                "the checkpoint was made with iterative deepening on in one run and off in the other"
+            ),
+            CheckpointError::AllOptimal => {
+                write!(f, "a search for every optimal board can't be resumed")
+            }
+            CheckpointError::DifferentObjective { saved } => write!(
+                f,
+                "the checkpoint was made minimizing {}",
+                saved.as_deref().unwrap_or("area")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+//FNV-1a, which unlike the standard hasher is the same from one build to the next
+pub(crate) fn hash_words(words: &[String]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for word in words {
+        for byte in word.bytes().chain(std::iter::once(b'\n')) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+#[test]
+fn word_hashes_depend_on_order() {
+    let words = |list: &[&str]| -> Vec<String> { list.iter().map(|w| w.to_string()).collect() };
+    assert_eq!(
+        hash_words(&words(&["ab", "c"])),
+        hash_words(&words(&["ab", "c"]))
+    );
+    assert_ne!(
+        hash_words(&words(&["ab", "c"])),
+        hash_words(&words(&["c", "ab"]))
+    );
+    assert_ne!(
+        hash_words(&words(&["ab", "c"])),
+        hash_words(&words(&["a", "bc"]))
+    );
+}
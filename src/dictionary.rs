@@ -0,0 +1,90 @@
+use crate::grid::can_be_made_with;
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{self, BufRead},
+    iter::FromIterator,
+    path::Path,
+};
+
+//a list of legal words, kept in order alongside a set for fast lookups
+#[derive(Debug, Clone, Default)]
+pub struct Dictionary {
+    words: Vec<String>,
+    word_set: HashSet<String>,
+}
+
+impl Dictionary {
+    //one word per line
+    pub fn from_file<P: AsRef<Path>>(filename: P) -> io::Result<Dictionary> {
+        let file = File::open(filename)?;
+        io::BufReader::new(file).lines().collect()
+    }
+
+    pub fn from_slice<S: AsRef<str>>(words: &[S]) -> Dictionary {
+        words.iter().map(|w| w.as_ref().to_string()).collect()
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.word_set.contains(word)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    //a new dictionary holding only the words that satisfy the predicate
+    pub fn filter<F: FnMut(&str) -> bool>(&self, mut predicate: F) -> Dictionary {
+        self.words
+            .iter()
+            .filter(|w| predicate(w))
+            .cloned()
+            .collect()
+    }
+
+    //only the words that can be assembled from these tiles
+    pub fn for_tiles(&self, tiles: &[char]) -> Dictionary {
+        self.filter(|word| can_be_made_with(word, tiles))
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Dictionary {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut dictionary = Dictionary::default();
+        dictionary.extend(iter);
+        dictionary
+    }
+}
+
+impl<S: Into<String>> Extend<S> for Dictionary {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        for word in iter {
+            let word = word.into();
+            let word = word.trim();
+            if word.is_empty() || self.word_set.contains(word) {
+                continue;
+            }
+            self.word_set.insert(word.to_string());
+            self.words.push(word.to_string());
+        }
+    }
+}
+
+#[test]
+fn dictionary_for_tiles() {
+    let dictionary = Dictionary::from_slice(&["cat", "act", "tact", "dog", "at", "at"]);
+    assert_eq!(dictionary.len(), 5);
+    let filtered = dictionary.for_tiles(&['t', 'a', 'c']);
+    assert_eq!(filtered.words(), &["cat", "act", "at"]);
+    assert!(filtered.contains("act"));
+    assert!(!filtered.contains("tact"));
+    assert!(dictionary.contains("tact"));
+}
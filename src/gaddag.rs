@@ -0,0 +1,402 @@
+//GADDAG over a word list: every word is stored once per split point as the
+//reversed prefix up to that letter, a turn marker, then the rest of the word.
+//starting from an anchor square this lets a placement grow leftwards and then
+//rightwards through letters already on the board without backtracking over
+//words that could never fit
+use crate::grid::{Direction, Grid, LetterPlacement};
+
+const TURN: char = '\0';
+
+#[derive(Debug, Clone, Default)]
+struct GaddagNode {
+    //sorted by letter, the turn marker sorts first
+    children: Vec<(char, usize)>,
+    terminal: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Gaddag {
+    nodes: Vec<GaddagNode>,
+}
+
+impl Gaddag {
+    pub fn new(words: &[String]) -> Gaddag {
+        let mut gaddag = Gaddag {
+            nodes: vec![GaddagNode::default()],
+        };
+        for word in words {
+            gaddag.insert(word);
+        }
+        gaddag
+    }
+
+    pub fn insert(&mut self, word: &str) {
+        let letters: Vec<char> = word.chars().collect();
+        for split in 1..letters.len() + 1 {
+            let path = letters[..split]
+                .iter()
+                .rev()
+                .chain(std::iter::once(&TURN))
+                .chain(letters[split..].iter());
+            let mut node = 0;
+            for &letter in path {
+                node = match self.child(node, letter) {
+                    Some(next) => next,
+                    None => {
+                        let next = self.nodes.len();
+                        self.nodes.push(GaddagNode::default());
+                        let children = &mut self.nodes[node].children;
+                        let index = children.partition_point(|(c, _)| *c < letter);
+                        children.insert(index, (letter, next));
+                        next
+                    }
+                };
+            }
+            self.nodes[node].terminal = true;
+        }
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        let mut letters = word.chars();
+        let mut node = match letters.next().and_then(|first| self.child(0, first)) {
+            Some(node) => node,
+            None => return false,
+        };
+        for letter in std::iter::once(TURN).chain(letters) {
+            match self.child(node, letter) {
+                Some(next) => node = next,
+                None => return false,
+            }
+        }
+        self.nodes[node].terminal
+    }
+
+    fn child(&self, node: usize, letter: char) -> Option<usize> {
+        let children = &self.nodes[node].children;
+        children
+            .binary_search_by_key(&letter, |(c, _)| *c)
+            .ok()
+            .map(|i| children[i].1)
+    }
+
+    //every placement of rack tiles which makes a word through this empty square,
+    //in either direction, with all the words it crosses still in the dictionary
+    #[cfg(test)]
+    fn placements_at(
+        &self,
+        board: &Grid,
+        anchor: (usize, usize),
+        rack: &[char],
+    ) -> Vec<Vec<LetterPlacement>> {
+        let mut result = Vec::new();
+        for &dir in &[Direction::Horizontal, Direction::Vertical] {
+            let mut walk = LineWalk::new(self, board, dir, anchor, rack, false);
+            walk.generate();
+            result.extend(walk.results);
+        }
+        result
+    }
+
+    //every placement of rack tiles that connects to the letters on the board and
+    //leaves it a valid bananagrams board, each generated exactly once
+    pub fn placements(&self, board: &Grid, rack: &[char]) -> Vec<Vec<LetterPlacement>> {
+        let mut result = Vec::new();
+        let bounds = board.bounding_box();
+        let (rows, cols) = board.dim();
+        if bounds.min_row > bounds.max_row {
+            return result;
+        }
+        for &dir in &[Direction::Horizontal, Direction::Vertical] {
+            for row in bounds.min_row.saturating_sub(1)..(bounds.max_row + 2).min(rows) {
+                for col in bounds.min_col.saturating_sub(1)..(bounds.max_col + 2).min(cols) {
+                    if !is_anchor(board, row, col) {
+                        continue;
+                    }
+                    let mut walk = LineWalk::new(self, board, dir, (row, col), rack, true);
+                    walk.generate();
+                    //a lone tile makes words both ways, keep the horizontal copy
+                    result.extend(walk.results.into_iter().filter(|placement| {
+                        dir == Direction::Horizontal
+                            || placement.len() > 1
+                            || (cell(board, row as isize, col as isize - 1) == ' '
+                                && cell(board, row as isize, col as isize + 1) == ' ')
+                    }));
+                }
+            }
+        }
+        result
+    }
+}
+
+fn cell(board: &Grid, row: isize, col: isize) -> char {
+    let (rows, cols) = board.dim();
+    if row < 0 || col < 0 || row as usize >= rows || col as usize >= cols {
+        ' '
+    } else {
+        board.get(row as usize, col as usize)
+    }
+}
+
+//an empty square next to a letter
+fn is_anchor(board: &Grid, row: usize, col: usize) -> bool {
+    let (r, c) = (row as isize, col as isize);
+    board.get(row, col) == ' '
+        && [(r - 1, c), (r + 1, c), (r, c - 1), (r, c + 1)]
+            .iter()
+            .any(|&(r, c)| cell(board, r, c) != ' ')
+}
+
+//generates the placements along one row or column through an anchor
+struct LineWalk<'a> {
+    gaddag: &'a Gaddag,
+    board: &'a Grid,
+    dir: Direction,
+    //the row of a horizontal line or the column of a vertical one
+    line: usize,
+    length: isize,
+    anchor: isize,
+    //where the word being built begins, once it has turned rightwards
+    start: isize,
+    //leave placements reaching over other anchors to the left for those anchors
+    avoid_anchors: bool,
+    rack: Vec<(char, usize)>,
+    placed: Vec<LetterPlacement>,
+    results: Vec<Vec<LetterPlacement>>,
+}
+
+impl<'a> LineWalk<'a> {
+    fn new(
+        gaddag: &'a Gaddag,
+        board: &'a Grid,
+        dir: Direction,
+        anchor: (usize, usize),
+        rack: &[char],
+        avoid_anchors: bool,
+    ) -> LineWalk<'a> {
+        let mut letters = rack.to_vec();
+        letters.sort_unstable();
+        let mut counts: Vec<(char, usize)> = Vec::new();
+        for letter in letters {
+            match counts.last_mut() {
+                Some((c, n)) if *c == letter => *n += 1,
+                _ => counts.push((letter, 1)),
+            }
+        }
+        let (rows, cols) = board.dim();
+        let (line, length, anchor) = match dir {
+            Direction::Horizontal => (anchor.0, cols, anchor.1),
+            Direction::Vertical => (anchor.1, rows, anchor.0),
+        };
+        LineWalk {
+            gaddag,
+            board,
+            dir,
+            line,
+            length: length as isize,
+            anchor: anchor as isize,
+            start: anchor as isize,
+            avoid_anchors,
+            rack: counts,
+            placed: Vec::new(),
+            results: Vec::new(),
+        }
+    }
+
+    fn generate(&mut self) {
+        if self.cell(self.anchor) == ' ' {
+            self.left(0, self.anchor);
+        }
+    }
+
+    //(row, col) of a position along the line
+    fn coords(&self, i: isize) -> (isize, isize) {
+        match self.dir {
+            Direction::Horizontal => (self.line as isize, i),
+            Direction::Vertical => (i, self.line as isize),
+        }
+    }
+
+    fn cell(&self, i: isize) -> char {
+        let (row, col) = self.coords(i);
+        cell(self.board, row, col)
+    }
+
+    //would putting this letter here leave a valid word across the line
+    fn cross_check(&self, i: isize, letter: char) -> bool {
+        let (row, col) = self.coords(i);
+        let (dr, dc) = match self.dir {
+            Direction::Horizontal => (1, 0),
+            Direction::Vertical => (0, 1),
+        };
+        let mut start = (row, col);
+        while cell(self.board, start.0 - dr, start.1 - dc) != ' ' {
+            start = (start.0 - dr, start.1 - dc);
+        }
+        let mut end = (row, col);
+        while cell(self.board, end.0 + dr, end.1 + dc) != ' ' {
+            end = (end.0 + dr, end.1 + dc);
+        }
+        if start == end {
+            return true;
+        }
+        let mut word = String::new();
+        let mut position = start;
+        loop {
+            word.push(if position == (row, col) {
+                letter
+            } else {
+                cell(self.board, position.0, position.1)
+            });
+            if position == end {
+                break;
+            }
+            position = (position.0 + dr, position.1 + dc);
+        }
+        self.gaddag.contains(&word)
+    }
+
+    //try each rack letter allowed at this empty position, calling next after placing it
+    fn place_each(&mut self, node: usize, i: isize, next: fn(&mut Self, usize, isize)) {
+        if i < 0 || i >= self.length {
+            return;
+        }
+        for r in 0..self.rack.len() {
+            let (letter, count) = self.rack[r];
+            if count == 0 {
+                continue;
+            }
+            let child = match self.gaddag.child(node, letter) {
+                Some(child) => child,
+                None => continue,
+            };
+            if !self.cross_check(i, letter) {
+                continue;
+            }
+            let (row, col) = self.coords(i);
+            self.rack[r].1 -= 1;
+            self.placed.push(LetterPlacement {
+                letter,
+                row: row as usize,
+                col: col as usize,
+            });
+            next(self, child, i);
+            self.placed.pop();
+            self.rack[r].1 += 1;
+        }
+    }
+
+    //extend the reversed prefix to cover position i
+    fn left(&mut self, node: usize, i: isize) {
+        match self.cell(i) {
+            ' ' => {
+                if self.avoid_anchors && i != self.anchor {
+                    let (row, col) = self.coords(i);
+                    if row >= 0 && col >= 0 && is_anchor(self.board, row as usize, col as usize) {
+                        return;
+                    }
+                }
+                self.place_each(node, i, Self::after_left);
+            }
+            letter => {
+                if let Some(child) = self.gaddag.child(node, letter) {
+                    self.after_left(child, i);
+                }
+            }
+        }
+    }
+
+    //the word now starts at i: keep going left, or turn and finish it to the right
+    fn after_left(&mut self, node: usize, i: isize) {
+        if self.cell(i - 1) != ' ' {
+            self.left(node, i - 1);
+            return;
+        }
+        self.left(node, i - 1);
+        if let Some(turn) = self.gaddag.child(node, TURN) {
+            self.start = i;
+            self.record(turn, self.anchor);
+            self.right(turn, self.anchor + 1);
+        }
+    }
+
+    //extend the suffix to cover position j
+    fn right(&mut self, node: usize, j: isize) {
+        match self.cell(j) {
+            ' ' => self.place_each(node, j, Self::after_right),
+            letter => {
+                if let Some(child) = self.gaddag.child(node, letter) {
+                    self.after_right(child, j);
+                }
+            }
+        }
+    }
+
+    fn after_right(&mut self, node: usize, j: isize) {
+        self.record(node, j);
+        self.right(node, j + 1);
+    }
+
+    //keep the placement if the word from start to end is finished here
+    fn record(&mut self, node: usize, end: isize) {
+        if self.gaddag.nodes[node].terminal && end > self.start && self.cell(end + 1) == ' ' {
+            self.results.push(self.placed.clone());
+        }
+    }
+}
+
+#[test]
+fn gaddag_contains() {
+    let words: Vec<String> = ["cat", "cats", "at"]
+        .iter()
+        .map(|w| w.to_string())
+        .collect();
+    let gaddag = Gaddag::new(&words);
+    assert!(gaddag.contains("cat"));
+    assert!(gaddag.contains("cats"));
+    assert!(gaddag.contains("at"));
+    assert!(!gaddag.contains("ca"));
+    assert!(!gaddag.contains("ta"));
+}
+
+#[test]
+fn placements_keep_board_valid() {
+    use crate::dictionary::Dictionary;
+    let dictionary =
+        Dictionary::from_slice(&["cat", "cats", "scat", "at", "ta", "act", "tact", "a"]);
+    let gaddag = Gaddag::new(dictionary.words());
+    let mut board = Grid::new(8, 8);
+    for (i, letter) in "cat".chars().enumerate() {
+        board.insert(3, 2 + i, letter);
+    }
+    let rack = ['a', 't', 'c', 's'];
+    let placements = gaddag.placements(&board, &rack);
+    assert!(!placements.is_empty());
+    let mut seen = Vec::new();
+    for placement in &placements {
+        let mut next = board.clone();
+        for ltr in placement {
+            next.place_letter(ltr);
+        }
+        assert!(next.valid_bananagrams(&dictionary), "{}", next);
+        assert!(!seen.contains(&next.to_string()), "{}", next);
+        seen.push(next.to_string());
+    }
+    //extending cat either way makes cats and scat
+    for col in &[1, 5] {
+        assert!(placements.contains(&vec![LetterPlacement {
+            letter: 's',
+            row: 3,
+            col: *col
+        }]));
+    }
+    //every placement through a square is found from it as well
+    let at_anchor = gaddag.placements_at(&board, (4, 3), &rack);
+    assert!(at_anchor
+        .iter()
+        .all(|p| p.iter().any(|l| (l.row, l.col) == (4, 3))));
+    assert!(at_anchor.contains(&vec![LetterPlacement {
+        letter: 't',
+        row: 4,
+        col: 3
+    }]));
+}
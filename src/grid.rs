@@ -0,0 +1,816 @@
+use crate::dictionary::Dictionary;
+use ndarray::s;
+use ndarray::Array2;
+use serde::Serialize;
+use std::{
+    cmp::{max, min},
+    fmt,
+    iter::FromIterator,
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LetterPlacement {
+    pub letter: char,
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min_col: usize,
+    pub max_col: usize,
+    pub min_row: usize,
+    pub max_row: usize,
+}
+
+impl BoundingBox {
+    pub fn width(&self) -> usize {
+        (self.max_col + 1).saturating_sub(self.min_col)
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max_row + 1).saturating_sub(self.min_row)
+    }
+
+    pub fn area(&self) -> usize {
+        max(
+            (self.max_row as isize - self.min_row as isize + 1)
+                * (self.max_col as isize - self.min_col as isize + 1),
+            0,
+        ) as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Vertical,
+    Horizontal,
+}
+
+//a word on the board, starting from its first letter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlacedWord {
+    pub word: String,
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone)]
+pub struct Grid(Array2<char>);
+
+//a board's bounding box contents, one line per row, in whichever of its two
+//orientations sorts first
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BoardKey(String);
+
+impl Grid {
+    //an empty grid of the given dimensions
+    pub fn new(rows: usize, cols: usize) -> Grid {
+        Grid(Array2::from_elem((rows, cols), ' '))
+    }
+
+    //one string per row, spaces for empty cells; short rows are padded out
+    pub fn from_rows<S: AsRef<str>>(rows: &[S]) -> Grid {
+        let width = rows
+            .iter()
+            .map(|row| row.as_ref().chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut grid = Grid::new(rows.len(), width);
+        for (r, row) in rows.iter().enumerate() {
+            for (c, letter) in row.as_ref().chars().enumerate() {
+                grid.insert(r, c, letter);
+            }
+        }
+        grid
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.0.dim()
+    }
+
+    //rows become columns
+    pub fn transpose(&self) -> Grid {
+        Grid(self.0.t().to_owned())
+    }
+
+    //just the bounding box, so the top left letter is in row 0 or column 0
+    pub fn cropped(&self) -> Grid {
+        let bounds = self.bounding_box();
+        if bounds.area() == 0 {
+            return Grid::new(0, 0);
+        }
+        Grid(
+            self.0
+                .slice(s![
+                    bounds.min_row..bounds.max_row + 1,
+                    bounds.min_col..bounds.max_col + 1
+                ])
+                .to_owned(),
+        )
+    }
+
+    pub fn rows(&self) -> Vec<String> {
+        self.0
+            .outer_iter()
+            .map(|row| row.iter().collect())
+            .collect()
+    }
+
+    pub fn tile_count(&self) -> usize {
+        self.0.iter().filter(|&&c| c != ' ').count()
+    }
+
+    //the same for boards that are translations or transposes of each other,
+    //and different for any other pair of boards
+    pub fn canonical_key(&self) -> BoardKey {
+        let bounds = self.bounding_box();
+        let mut rows = String::new();
+        for r in bounds.min_row..bounds.max_row + 1 {
+            rows.extend(self.0.slice(s![r, bounds.min_col..bounds.max_col + 1]));
+            rows.push('\n');
+        }
+        let mut cols = String::new();
+        for c in bounds.min_col..bounds.max_col + 1 {
+            cols.extend(self.0.slice(s![bounds.min_row..bounds.max_row + 1, c]));
+            cols.push('\n');
+        }
+        BoardKey(min(rows, cols))
+    }
+
+    pub fn print(&self) {
+        print!("{}", self);
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox {
+        let (rows, cols) = self.0.dim();
+        let mut min_col = cols;
+        let mut max_col = 0;
+        let mut min_row = rows;
+        let mut max_row = 0;
+        for r in 0..rows {
+            for c in 0..cols {
+                if self.0[[r, c]] != ' ' {
+                    min_col = min(min_col, c);
+                    max_col = max(max_col, c);
+                    min_row = min(min_row, r);
+                    max_row = max(max_row, r);
+                }
+            }
+        }
+        BoundingBox {
+            min_col,
+            max_col,
+            min_row,
+            max_row,
+        }
+    }
+
+    pub fn fits_in_row(
+        &self,
+        word: &str,
+        position: usize,
+        dir: Direction,
+        available_chars: &[char],
+    ) -> bool {
+        let words = self.words_at(position, dir);
+        fits_in_row(word, available_chars, &words)
+    }
+
+    pub fn bounding_box_area(&self) -> usize {
+        self.bounding_box().area()
+    }
+
+    pub fn valid_bananagrams(&self, dictionary: &Dictionary) -> bool {
+        let bounds = self.bounding_box();
+        let mut words_to_check: Vec<String> = Vec::new();
+        for row in bounds.min_row..bounds.max_row + 1 {
+            words_to_check.extend(
+                self.words_at(row, Direction::Horizontal)
+                    .split_whitespace()
+                    .map(|x| x.to_string()),
+            );
+        }
+        for col in bounds.min_col..bounds.max_col + 1 {
+            words_to_check.extend(
+                self.words_at(col, Direction::Vertical)
+                    .split_whitespace()
+                    .map(|x| x.to_string()),
+            );
+        }
+        for word in &words_to_check {
+            if !dictionary.contains(word) && word.len() > 1 {
+                return false;
+            }
+        }
+        self.is_connected()
+    }
+
+    //every tile can be reached from every other through neighbouring tiles
+    pub fn is_connected(&self) -> bool {
+        self.islands().len() <= 1
+    }
+
+    //groups of tiles connected to each other but not to any other group,
+    //each listed as (row, col) in the order a flood fill reaches them
+    pub fn islands(&self) -> Vec<Vec<(usize, usize)>> {
+        let (rows, cols) = self.0.dim();
+        let bounds = self.bounding_box();
+        let mut seen = Array2::from_elem((rows, cols), false);
+        let mut islands = Vec::new();
+        for row in bounds.min_row..bounds.max_row + 1 {
+            for col in bounds.min_col..bounds.max_col + 1 {
+                if seen[[row, col]] || self.get(row, col) == ' ' {
+                    continue;
+                }
+                let mut island = Vec::new();
+                let mut stack = vec![(row, col)];
+                seen[[row, col]] = true;
+                while let Some((r, c)) = stack.pop() {
+                    island.push((r, c));
+                    let neighbours = [
+                        (r.wrapping_sub(1), c),
+                        (r + 1, c),
+                        (r, c.wrapping_sub(1)),
+                        (r, c + 1),
+                    ];
+                    for &(nr, nc) in &neighbours {
+                        if nr < rows && nc < cols && !seen[[nr, nc]] && self.get(nr, nc) != ' ' {
+                            seen[[nr, nc]] = true;
+                            stack.push((nr, nc));
+                        }
+                    }
+                }
+                islands.push(island);
+            }
+        }
+        islands
+    }
+
+    //every run of two or more letters across or down
+    pub fn placed_words(&self) -> Vec<PlacedWord> {
+        let bounds = self.bounding_box();
+        let mut result = Vec::new();
+        for &direction in &[Direction::Horizontal, Direction::Vertical] {
+            let (lines, along) = match direction {
+                Direction::Horizontal => (
+                    bounds.min_row..bounds.max_row + 1,
+                    bounds.min_col..bounds.max_col + 2,
+                ),
+                Direction::Vertical => (
+                    bounds.min_col..bounds.max_col + 1,
+                    bounds.min_row..bounds.max_row + 2,
+                ),
+            };
+            for line in lines {
+                let mut word = String::new();
+                for i in along.clone() {
+                    let (row, col) = match direction {
+                        Direction::Horizontal => (line, i),
+                        Direction::Vertical => (i, line),
+                    };
+                    let letter = if row < self.0.dim().0 && col < self.0.dim().1 {
+                        self.get(row, col)
+                    } else {
+                        ' '
+                    };
+                    if letter != ' ' {
+                        word.push(letter);
+                        continue;
+                    }
+                    let length = word.chars().count();
+                    if length > 1 {
+                        let start = i - length;
+                        let (row, col) = match direction {
+                            Direction::Horizontal => (line, start),
+                            Direction::Vertical => (start, line),
+                        };
+                        result.push(PlacedWord {
+                            word: word.clone(),
+                            row,
+                            col,
+                            direction,
+                        });
+                    }
+                    word.clear();
+                }
+            }
+        }
+        result
+    }
+
+    pub fn word_placements_for(
+        &self,
+        word: &str,
+        position: usize,
+        dir: Direction,
+    ) -> Vec<Vec<LetterPlacement>> {
+        let mut result = Vec::new();
+        let bounds = self.bounding_box();
+        let lower = match dir {
+            Direction::Horizontal => bounds.min_col,
+            Direction::Vertical => bounds.min_row,
+        };
+        for i in lower.saturating_sub(word.len())..lower + 1 {
+            let mut this_result: Vec<LetterPlacement> = Vec::new();
+            let mut connected: bool = false;
+            for j in 0..word.len() {
+                let row = match dir {
+                    Direction::Horizontal => position,
+                    Direction::Vertical => i + j,
+                };
+                let col = match dir {
+                    Direction::Horizontal => i + j,
+                    Direction::Vertical => position,
+                };
+                let letter = word.chars().nth(j).unwrap();
+                match self.get(row, col) {
+                    ' ' => {
+                        this_result.push(LetterPlacement { letter, row, col });
+                        continue;
+                    }
+                    x if x == letter => connected = true,
+                    _ => break,
+                };
+            }
+            if !this_result.is_empty() && connected {
+                result.push(this_result);
+            }
+        }
+        result
+    }
+
+    pub fn words_at(&self, position: usize, dir: Direction) -> String {
+        let chars = match dir {
+            Direction::Horizontal => self.0.slice(s![position..position + 1, ..]), //row
+            Direction::Vertical => self.0.slice(s![.., position..position + 1]),   //column
+        };
+        String::from_iter(chars)
+    }
+
+    pub fn insert(&mut self, r: usize, c: usize, val: char) {
+        self.0[[r, c]] = val;
+    }
+
+    pub fn get(&self, r: usize, c: usize) -> char {
+        self.0[[r, c]]
+    }
+
+    pub fn clear(&mut self) {
+        for row in 0..self.0.dim().0 {
+            for col in 0..self.0.dim().1 {
+                self.insert(row, col, ' ');
+            }
+        }
+    }
+
+    pub fn place_letter(&mut self, pl: &LetterPlacement) {
+        self.insert(pl.row, pl.col, pl.letter);
+    }
+
+    pub fn remove_letter(&mut self, pl: &LetterPlacement) {
+        self.insert(pl.row, pl.col, ' ');
+    }
+
+    pub fn midpoint(&self) -> (usize, usize) {
+        (self.0.dim().0 / 2, self.0.dim().1 / 2)
+    }
+}
+
+//same format the solver has always printed: space separated cells, then the area
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in 0..self.0.dim().0 {
+            for col in 0..self.0.dim().1 {
+                write!(f, "{} ", self.0[[row, col]])?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "Area: {}", self.bounding_box_area())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGridError {
+    //cells are separated by single spaces, so every other character must be one
+    MissingSeparator { row: usize, col: usize },
+    //the "Area: n" line doesn't match the board above it
+    WrongArea { stated: String, actual: usize },
+    Empty,
+}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseGridError::MissingSeparator { row, col } => write!(
+                f,
+                "row {}: expected a space after the cell in column {}",
+                row, col
+            ),
+            ParseGridError::WrongArea { stated, actual } => {
+                write!(f, "board says its area is {} but it is {}", stated, actual)
+            }
+            ParseGridError::Empty => write!(f, "no board found"),
+        }
+    }
+}
+
+impl std::error::Error for ParseGridError {}
+
+//reads boards in the format Display writes them
+impl FromStr for Grid {
+    type Err = ParseGridError;
+
+    fn from_str(text: &str) -> Result<Grid, ParseGridError> {
+        let mut rows: Vec<String> = Vec::new();
+        let mut stated_area = None;
+        for (r, line) in text.lines().enumerate() {
+            if let Some(area) = line.trim().strip_prefix("Area:") {
+                stated_area = Some(area.trim().to_string());
+                break;
+            }
+            let mut row = String::new();
+            for (i, c) in line.trim_end().chars().enumerate() {
+                if i % 2 == 0 {
+                    row.push(c);
+                } else if c != ' ' {
+                    return Err(ParseGridError::MissingSeparator { row: r, col: i / 2 });
+                }
+            }
+            rows.push(row);
+        }
+        let grid = Grid::from_rows(&rows);
+        if grid.0.iter().all(|c| *c == ' ') {
+            return Err(ParseGridError::Empty);
+        }
+        if let Some(stated) = stated_area {
+            let actual = grid.bounding_box_area();
+            if stated.parse() != Ok(actual) {
+                return Err(ParseGridError::WrongArea { stated, actual });
+            }
+        }
+        Ok(grid)
+    }
+}
+
+//can this word be assembled from these tiles?
+pub fn can_be_made_with(word: &str, tiles: &[char]) -> bool {
+    let mut tiles = tiles.to_owned();
+    for c in word.chars() {
+        match tiles.iter().position(|x| *x == c) {
+            None => return false,
+            Some(index) => tiles.remove(index),
+        };
+    }
+    true
+}
+
+//can this word be assembled from these tiles
+//given that it also needs to be placed somewhere in row
+pub fn fits_in_row(word: &str, tiles: &[char], row: &str) -> bool {
+    let mut all_tiles = tiles.to_owned();
+    all_tiles.extend(row.chars().filter(|c| *c != ' ').collect::<Vec<char>>());
+    if !can_be_made_with(word, &all_tiles) {
+        return false;
+    }
+    //trim and pad with spaces
+    let row = format!("{:pad$}{}{:pad$}", "", row.trim(), "", pad = word.len() - 1);
+    let mut row_with_word_inserted = row.clone();
+    'outer: for start_index in 0..row.len() - word.len() + 1 {
+        row_with_word_inserted.replace_range(.., &row);
+        row_with_word_inserted.replace_range(start_index..start_index + word.len(), word);
+        for (i, c) in row.chars().enumerate() {
+            if c != ' ' && c != row_with_word_inserted.chars().nth(i).unwrap() {
+                continue 'outer;
+            }
+        }
+        //at this point we know no row characters have been replaced
+        if row_with_word_inserted.split_whitespace().any(|x| x == word) {
+            return true;
+        }
+    }
+    false
+}
+
+pub fn place_word_at(word: &str, c0: usize, r0: usize, dir: Direction) -> Vec<LetterPlacement> {
+    let mut result = Vec::new();
+    for (i, c) in word.chars().enumerate() {
+        result.push(match dir {
+            Direction::Horizontal => LetterPlacement {
+                letter: c,
+                col: c0 + i,
+                row: r0,
+            },
+            Direction::Vertical => LetterPlacement {
+                letter: c,
+                col: c0,
+                row: r0 + i,
+            },
+        });
+    }
+    result
+}
+
+#[test]
+fn bounding_box() {
+    let mut grid = Grid::new(10, 10);
+    grid.insert(5, 5, 'o');
+    let bounds = grid.bounding_box();
+    assert_eq!(bounds.min_row, 5);
+    assert_eq!(bounds.max_row, 5);
+    assert_eq!(bounds.min_col, 5);
+    assert_eq!(bounds.max_col, 5);
+    grid.insert(7, 6, 'o');
+    let bounds = grid.bounding_box();
+    assert_eq!(bounds.min_row, 5);
+    assert_eq!(bounds.max_row, 7);
+    assert_eq!(bounds.min_col, 5);
+    assert_eq!(bounds.max_col, 6);
+    grid.insert(3, 4, 'o');
+    let bounds = grid.bounding_box();
+    assert_eq!(bounds.min_row, 3);
+    assert_eq!(bounds.max_row, 7);
+    assert_eq!(bounds.min_col, 4);
+    assert_eq!(bounds.max_col, 6);
+    assert_eq!(grid.bounding_box_area(), 15);
+}
+
+#[test]
+fn fits_in_row_1() {
+    let tiles = "ab".chars().collect::<Vec<char>>();
+    let row = "   a c ";
+    assert!(fits_in_row("abc", &tiles, row));
+    assert!(!fits_in_row("abcb", &tiles, row));
+    assert!(fits_in_row("cab", &tiles, row));
+    assert!(!fits_in_row("abac", &tiles, row));
+}
+
+#[test]
+fn fits_in_row_2() {
+    let tiles = "ab".chars().collect::<Vec<char>>();
+    let row = "a a";
+    let good_words = vec!["aa", "aba", "a"];
+    let bad_words = vec!["abaca", "cabac", "abbabababababbaa"];
+    for word in good_words {
+        assert!(fits_in_row(word, &tiles, row));
+    }
+    for word in bad_words {
+        assert!(!fits_in_row(word, &tiles, row));
+    }
+}
+
+#[test]
+fn hash_grids() {
+    let mut board = Grid::new(5, 5);
+    let empty_hash = board.canonical_key();
+    board.insert(1, 1, 'h');
+    assert!(board.canonical_key() != empty_hash);
+    board.insert(1, 1, ' ');
+    assert!(board.canonical_key() == empty_hash);
+    let board2 = Grid::new(5, 5);
+    assert!(board2.canonical_key() == empty_hash);
+}
+
+#[test]
+fn hash_offset() {
+    let mut board = Grid::new(5, 5);
+    let empty_hash = board.canonical_key();
+    board.insert(1, 1, 'h');
+    board.insert(1, 2, 'i');
+    board.insert(2, 1, 'i');
+    let hi_hash = board.canonical_key();
+    assert!(board.canonical_key() != empty_hash);
+    board.clear();
+    assert!(board.canonical_key() == empty_hash);
+    board.insert(2, 2, 'h');
+    board.insert(2, 3, 'i');
+    board.insert(3, 2, 'i');
+    assert!(board.canonical_key() == hi_hash);
+}
+
+#[test]
+fn hash_transpose() {
+    let mut board = Grid::new(5, 5);
+    let empty_hash = board.canonical_key();
+    board.insert(1, 1, 'h');
+    board.insert(1, 2, 'i');
+    board.insert(2, 1, 'e');
+    board.insert(3, 1, 'y');
+    let hi_hey_hash = board.canonical_key();
+    assert!(board.canonical_key() != empty_hash);
+    board.clear();
+    assert!(board.canonical_key() == empty_hash);
+    board.insert(1, 1, 'h');
+    board.insert(1, 2, 'e');
+    board.insert(1, 3, 'y');
+    board.insert(2, 1, 'i');
+    assert!(board.canonical_key() == hi_hey_hash);
+    board.insert(1, 1, ' ');
+    assert!(board.canonical_key() != hi_hey_hash);
+}
+
+#[test]
+fn distinct_boards_have_distinct_keys() {
+    let mut hi = Grid::new(5, 5);
+    hi.insert(1, 1, 'h');
+    hi.insert(1, 2, 'i');
+    let mut ih = Grid::new(5, 5);
+    ih.insert(1, 1, 'i');
+    ih.insert(1, 2, 'h');
+    assert!(hi.canonical_key() != ih.canonical_key());
+    let mut wide = Grid::new(5, 5);
+    wide.insert(0, 0, 'a');
+    wide.insert(0, 2, 'a');
+    let mut tall = Grid::new(5, 5);
+    tall.insert(0, 0, 'a');
+    tall.insert(1, 0, 'a');
+    tall.insert(2, 0, 'a');
+    assert!(wide.canonical_key() != tall.canonical_key());
+}
+
+#[test]
+fn canonical_keys_are_exact() {
+    //every 2x3 board over {' ', a, b}, placed at two offsets, compared against
+    //every other: keys match exactly when the cropped boards are equal or transposed
+    let cells = [' ', 'a', 'b'];
+    let mut boards: Vec<Vec<Vec<char>>> = Vec::new();
+    for n in 0..3usize.pow(6) {
+        let mut board = vec![vec![' '; 3]; 2];
+        let mut n = n;
+        for cell in board.iter_mut().flatten() {
+            *cell = cells[n % 3];
+            n /= 3;
+        }
+        boards.push(board);
+    }
+    //trims empty rows and columns off the edges
+    let crop = |board: &Vec<Vec<char>>| -> Vec<Vec<char>> {
+        let rows: Vec<usize> = (0..board.len())
+            .filter(|&r| board[r].iter().any(|c| *c != ' '))
+            .collect();
+        let cols: Vec<usize> = (0..board[0].len())
+            .filter(|&c| board.iter().any(|row| row[c] != ' '))
+            .collect();
+        match (rows.first(), rows.last(), cols.first(), cols.last()) {
+            (Some(&r0), Some(&r1), Some(&c0), Some(&c1)) => (r0..r1 + 1)
+                .map(|r| board[r][c0..c1 + 1].to_vec())
+                .collect(),
+            _ => Vec::new(),
+        }
+    };
+    let transpose = |board: &Vec<Vec<char>>| -> Vec<Vec<char>> {
+        if board.is_empty() {
+            return Vec::new();
+        }
+        (0..board[0].len())
+            .map(|c| board.iter().map(|row| row[c]).collect())
+            .collect()
+    };
+    let grid_at = |board: &Vec<Vec<char>>, offset: usize| -> Grid {
+        let mut grid = Grid::new(6, 6);
+        for (r, row) in board.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                grid.insert(r + offset, c + 2 * offset, *cell);
+            }
+        }
+        grid
+    };
+    let keys: Vec<BoardKey> = boards
+        .iter()
+        .map(|b| grid_at(b, 0).canonical_key())
+        .collect();
+    for (i, a) in boards.iter().enumerate() {
+        assert_eq!(grid_at(a, 1).canonical_key(), keys[i]);
+        let a = crop(a);
+        for (j, b) in boards.iter().enumerate() {
+            let b = crop(b);
+            let same = a == b || a == transpose(&b);
+            assert_eq!(same, keys[i] == keys[j]);
+        }
+    }
+}
+
+#[test]
+fn disconnected_boards_are_invalid() {
+    let dictionary = Dictionary::from_slice(&["hi", "he", "hey", "yo"]);
+    let mut board = Grid::new(6, 6);
+    for (i, letter) in "hi".chars().enumerate() {
+        board.insert(0, i, letter);
+    }
+    assert!(board.valid_bananagrams(&dictionary));
+    //a second word off on its own used to pass
+    for (i, letter) in "hey".chars().enumerate() {
+        board.insert(4, 2 + i, letter);
+    }
+    assert!(!board.is_connected());
+    assert_eq!(board.islands().len(), 2);
+    assert!(!board.valid_bananagrams(&dictionary));
+    //only touching at a corner isn't connected either
+    board.clear();
+    board.insert(0, 0, 'h');
+    board.insert(0, 1, 'i');
+    board.insert(1, 2, 'y');
+    board.insert(2, 2, 'o');
+    assert!(!board.valid_bananagrams(&dictionary));
+    board.insert(1, 0, 'e');
+    board.insert(2, 0, 'y');
+    assert_eq!(board.islands().len(), 2);
+    //joined up through the bottom row: hey down, yo across
+    board.clear();
+    for (i, letter) in "hey".chars().enumerate() {
+        board.insert(i, 0, letter);
+    }
+    board.insert(2, 1, 'o');
+    board.insert(0, 1, 'i');
+    assert!(board.is_connected());
+    assert!(board.valid_bananagrams(&dictionary));
+}
+
+#[test]
+fn placed_words() {
+    let board = Grid::from_rows(&["hi ", "e  ", "yo "]);
+    let words = board.placed_words();
+    assert_eq!(
+        words,
+        vec![
+            PlacedWord {
+                word: "hi".to_string(),
+                row: 0,
+                col: 0,
+                direction: Direction::Horizontal
+            },
+            PlacedWord {
+                word: "yo".to_string(),
+                row: 2,
+                col: 0,
+                direction: Direction::Horizontal
+            },
+            PlacedWord {
+                word: "hey".to_string(),
+                row: 0,
+                col: 0,
+                direction: Direction::Vertical
+            },
+        ]
+    );
+}
+
+#[test]
+fn cropped_rows() {
+    let board = Grid::from_rows(&["     ", "  hi ", "  e  ", "     "]);
+    assert_eq!(board.cropped().rows(), vec!["hi", "e "]);
+    assert_eq!(board.cropped().placed_words()[0].col, 0);
+    assert!(Grid::new(3, 3).cropped().rows().is_empty());
+}
+
+#[test]
+fn parses_printed_boards() {
+    let board = Grid::from_rows(&["    ", " hi ", " e  ", " yo "]);
+    let parsed: Grid = board.to_string().parse().unwrap();
+    assert_eq!(parsed.canonical_key(), board.canonical_key());
+    assert_eq!(parsed.get(1, 1), 'h');
+    //editors often strip trailing spaces
+    let trimmed: Grid = "h i\ne\ny o\n".parse().unwrap();
+    assert_eq!(trimmed.canonical_key(), board.canonical_key());
+    assert_eq!(
+        "hi\n".parse::<Grid>().unwrap_err(),
+        ParseGridError::MissingSeparator { row: 0, col: 0 }
+    );
+    assert_eq!(
+        "h i \nArea: 3\n".parse::<Grid>().unwrap_err(),
+        ParseGridError::WrongArea {
+            stated: "3".to_string(),
+            actual: 2
+        }
+    );
+    assert_eq!("  \n".parse::<Grid>().unwrap_err(), ParseGridError::Empty);
+}
+
+#[test]
+fn parses_wide_offset_boards() {
+    let parsed: Grid = "      h i\nArea: 2\n".parse().unwrap();
+    assert_eq!(parsed.dim(), (1, 5));
+    assert_eq!(parsed.bounding_box_area(), 2);
+    assert_eq!(parsed.cropped().rows(), vec!["hi".to_string()]);
+}
+
+#[test]
+fn bounding_boxes_of_non_square_grids() {
+    let wide = Grid::from_rows(&["   hi"]);
+    assert_eq!(
+        wide.bounding_box(),
+        BoundingBox {
+            min_col: 3,
+            max_col: 4,
+            min_row: 0,
+            max_row: 0
+        }
+    );
+    assert_eq!(wide.bounding_box_area(), 2);
+    let tall = Grid::from_rows(&[" ", " ", "h", "i"]);
+    assert_eq!(tall.bounding_box_area(), 2);
+    assert_eq!(tall.bounding_box().min_row, 2);
+}
@@ -0,0 +1,32 @@
+mod bag;
+mod checkpoint;
+mod dictionary;
+mod gaddag;
+mod grid;
+mod objective;
+mod solver;
+mod stats;
+mod transposition;
+mod trie;
+mod validation;
+
+pub use bag::Bag;
+pub use checkpoint::{Checkpoint, CheckpointError};
+pub use dictionary::Dictionary;
+pub use gaddag::Gaddag;
+pub use grid::{
+    can_be_made_with, fits_in_row, BoardKey, BoundingBox, Direction, Grid, LetterPlacement,
+    ParseGridError, PlacedWord,
+};
+pub use objective::{
+    Area, FewestWords, Lexicographic, LongestWord, MostSquare, MostTiles, MostWords, Objective,
+    Perimeter, Score,
+};
+pub use solver::{
+    area_lower_bound, BoardCount, MoveGenerator, ParetoBoard, ParetoFront, ParetoMeasure, Solution,
+    SolveEvent, SolveOptions, Solver, WordOrder,
+};
+pub use stats::{PhaseTimings, SearchStats};
+pub use transposition::{ReplacementPolicy, TableStats, TranspositionTable};
+pub use trie::Trie;
+pub use validation::ValidationReport;
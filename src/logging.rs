@@ -0,0 +1,33 @@
+//leveled messages on stderr, so stdout carries nothing but results
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    //errors and results only
+    Quiet,
+    //new smallest boards and deepening targets
+    Normal,
+    //the word list, each first word and the table summary
+    Verbose,
+    //every board the search reaches
+    Trace,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Normal as u8);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+macro_rules! log {
+    ($level:expr, $($arg:tt)*) => {
+        if crate::logging::enabled($level) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+pub(crate) use log;
@@ -1,638 +1,434 @@
-use lazy_static::lazy_static;
-use ndarray::s;
-use ndarray::Array2;
-use rand::prelude::*;
-use std::{
-    cmp::{max, min},
-    collections::{hash_map::DefaultHasher, HashMap, HashSet},
-    hash::Hasher,
-    iter::FromIterator,
+use bananagrams::{
+    Bag, Checkpoint, Dictionary, Grid, ParetoFront, ParetoMeasure, Solution, SolveEvent,
+    SolveOptions, Solver,
 };
 
 mod args;
 use args::*;
+mod logging;
+use logging::{log, Level};
+mod output;
+use output::{ParetoReport, SolveReport};
 
 use mimalloc::MiMalloc;
+use std::{
+    fs, io,
+    path::Path,
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
 
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
 
-//immutable static
-lazy_static! {
-    static ref PREEMPTIVE_CHECKING: bool = arg_exists("-c");
-    static ref ALL_WORDS: Vec<String> = {
-        let word_filename = after_flag_or("-f", "words.txt".to_string());
-        let mut words: Vec<String> = if let Ok(lines) = read_lines(&word_filename) {
-            lines.flatten().collect()
-        } else {
-            println!("file '{}' not found", word_filename);
-            return Vec::new();
-        };
-
-        let min_word_length: usize = after_flag_or("--min-word-length", 0);
-        let max_word_length: usize = after_flag_or("--max-word-length", std::usize::MAX);
-
-        let tileword: String = getarg(1, "loremipsum".to_string());
-        let tiles: Vec<char> = tileword.chars().collect();
-        words = words
-            .into_iter()
-            .filter(|word| word.len() >= min_word_length && word.len() <= max_word_length)
-            .filter(|word| can_be_made_with(word, &tiles))
-            .collect();
-        if arg_exists("-r") {
-            words.shuffle(&mut thread_rng());
-        }
-        if arg_exists("-s") {
-            words.sort_by_key(|a| a.len());
-        }
-        if arg_exists("-l") {
-            words.sort_by_key(|a| a.len());
-            words.reverse();
-        }
-        println!("{:?}", words);
-        words
-    };
-    static ref WORD_SET: HashSet<String> = ALL_WORDS.iter().map(|s| s.to_string()).collect();
-}
-
 fn main() {
-    if num_args() < 2 || arg_exists("-help") {
-        println!(
-            "Usage: ./bananagrams [tiles]
-Ex: ./bananagrams loremipsum -c -s -f common.txt
-Options:
-      -s to try shorter words first
-      -l to try longer words first
-      -c to check if valid at every step
-      -r to randomize word choosing order
-      -f to choose a file of words to draw from
-      --min-word-length to set a lower limit for word size
-      --max-word-length to set an upper limit for word size"
-        );
-        return;
-    }
-
-    let tileword: String = getarg(1, "loremipsum".to_string());
-    let tiles: Vec<char> = tileword.chars().collect();
-
-    let board_dim = tiles.len() * 2;
-    let mut state = SolveState {
-        minimum: None,
-        minimum_area: board_dim * board_dim,
-        hashed_boards: HashSet::new(),
-    };
-    find_minimum_area_configuration(
-        WordStackFrame {
-            remaining_tiles: tiles,
-            available_words: HashMap::new(),
-            recursion_depth: 0,
-        },
-        &mut state,
-        &mut Grid(Array2::from_elem((board_dim, board_dim), ' ')),
-    );
-    if let Some(min) = state.minimum {
-        println!("Minimum solution:");
-        min.print();
-    } else {
-        print!("Impossible to solve with these tiles");
+    let cli = Cli::parse_args();
+    logging::set_level(cli.level());
+    match cli.command {
+        Command::Solve(args) => solve(args),
+        Command::Verify(args) => verify(args),
+        Command::Bench(args) => bench(args),
+        Command::Generate(args) => generate(args),
+        Command::Play(args) => play(args),
+        Command::Count(args) => count(args),
     }
 }
 
-//structs
-#[derive(Debug, Clone)]
-struct LetterPlacement {
-    letter: char,
-    row: usize,
-    col: usize,
+//the dictionary and options every solving command starts from
+fn setup(search: &SearchArgs) -> (Dictionary, SolveOptions) {
+    let options = search.options().unwrap_or_else(|e| e.exit());
+    let dictionary = Dictionary::from_file(&search.words).unwrap_or_else(|e| {
+        eprintln!("couldn't read '{}': {}", search.words.display(), e);
+        process::exit(1);
+    });
+    (dictionary, options)
 }
 
-#[derive(Debug, Clone)]
-struct BoundingBox {
-    min_col: usize,
-    max_col: usize,
-    min_row: usize,
-    max_row: usize,
+//the first Ctrl-C stops the search so the best board so far is still reported,
+//a second one quits straight away
+fn cancel_on_interrupt(options: &mut SolveOptions) -> Arc<AtomicBool> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = cancel.clone();
+    let handler = ctrlc::set_handler(move || {
+        if flag.swap(true, Ordering::Relaxed) {
+            process::exit(130);
+        }
+        log!(
+            Level::Quiet,
+            "Interrupted, stopping with the best board so far (Ctrl-C again to quit)"
+        );
+    });
+    if let Err(e) = handler {
+        log!(Level::Verbose, "couldn't handle Ctrl-C: {}", e);
+    }
+    options.cancel = Some(cancel.clone());
+    cancel
 }
 
-impl BoundingBox {
-    fn area(&self) -> usize {
-        max(
-            (self.max_row as isize - self.min_row as isize + 1)
-                * (self.max_col as isize - self.min_col as isize + 1),
-            0,
-        ) as usize
+fn log_event(event: SolveEvent) {
+    match event {
+        SolveEvent::TargetArea(area) => {
+            log!(Level::Normal, "Searching for boards of area {}", area)
+        }
+        SolveEvent::FirstWord(word) => log!(Level::Verbose, "{}", word),
+        SolveEvent::NewMinimum(board) => log!(
+            Level::Normal,
+            "New Smallest Solution Found!\n{}",
+            board.to_string().trim_end()
+        ),
+        SolveEvent::Visit {
+            depth,
+            board,
+            remaining_tiles,
+        } => log!(
+            Level::Trace,
+            "{:indent$}depth {}: area {}, {} tiles left",
+            "",
+            depth,
+            board.bounding_box_area(),
+            remaining_tiles.len(),
+            indent = depth * 2
+        ),
+        SolveEvent::Checkpoint(_) => {}
     }
 }
 
-#[derive(Debug, Clone)]
-struct WordStackFrame {
-    remaining_tiles: Vec<char>,
-    available_words: HashMap<(Direction, usize), Vec<String>>,
-    recursion_depth: usize,
+//written next to the file and renamed over it, so a crash never leaves half a checkpoint
+fn write_checkpoint(path: &Path, checkpoint: &Checkpoint) {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let written = serde_json::to_string(checkpoint)
+        .map_err(io::Error::from)
+        .and_then(|json| fs::write(&temporary, json))
+        .and_then(|_| fs::rename(&temporary, path));
+    match written {
+        Ok(()) => log!(Level::Verbose, "Saved progress to '{}'", path.display()),
+        Err(e) => log!(
+            Level::Quiet,
+            "couldn't save progress to '{}': {}",
+            path.display(),
+            e
+        ),
+    }
 }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
-enum Direction {
-    Vertical,
-    Horizontal,
+fn read_checkpoint(path: &Path) -> Checkpoint {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("couldn't read '{}': {}", path.display(), e);
+        process::exit(1);
+    });
+    serde_json::from_str(&text).unwrap_or_else(|e| {
+        eprintln!("'{}' isn't a checkpoint: {}", path.display(), e);
+        process::exit(1);
+    })
 }
 
-#[derive(Debug, Clone)]
-struct Grid(Array2<char>);
-
-impl Grid {
-    fn hash(&self) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        let bounds = self.bounding_box();
-        for r in bounds.min_row..bounds.max_row + 1 {
-            for c in bounds.min_col..bounds.max_col + 1 {
-                ::core::hash::Hash::hash(&self.0[[r, c]], &mut hasher);
-            }
-        }
-        let self_hash = hasher.finish();
-        hasher = DefaultHasher::new();
-        for c in bounds.min_col..bounds.max_col + 1 {
-            for r in bounds.min_row..bounds.max_row + 1 {
-                ::core::hash::Hash::hash(&self.0[[r, c]], &mut hasher);
-            }
-        }
-        let transpose_hash = hasher.finish();
-        self_hash | transpose_hash
-    }
-    fn print(&self) {
-        for row in 0..self.0.dim().0 {
-            for col in 0..self.0.dim().1 {
-                print!("{} ", self.0[[row, col]]);
-            }
-            println!();
-        }
-        println!("Area: {}", self.bounding_box_area());
-    }
+//the search's counters, at level so an interrupted search can show them at any verbosity
+fn log_stats(solution: &Solution, level: Level) {
+    let table = solution.table_stats;
+    log!(
+        level,
+        "Board table: {} hits, {} misses, {} evictions",
+        table.hits,
+        table.misses,
+        table.evictions
+    );
+    let stats = &solution.stats;
+    log!(
+        level,
+        "Nodes: {} ({:?} by depth)\nPruned: {} by the table, {} by bound, {} outside the box, {} invalid\nCandidates: {} across, {} down ({:?} by row, {:?} by column)\nComplete boards: {}",
+        stats.nodes,
+        stats.nodes_per_depth,
+        stats.table_hits,
+        stats.bound_prunes,
+        stats.box_prunes,
+        stats.validity_prunes,
+        stats.horizontal_candidates,
+        stats.vertical_candidates,
+        stats.candidates_per_row,
+        stats.candidates_per_col,
+        stats.complete_boards
+    );
+    //phases are only timed when verbose, see SearchArgs::options
+    let t = &stats.timings;
+    log!(
+        Level::Verbose,
+        "Time: index {:.3?}, table {:.3?}, bound {:.3?}, validation {:.3?}, move generation {:.3?}",
+        t.index,
+        t.table,
+        t.bound,
+        t.validation,
+        t.move_generation
+    );
+}
 
-    fn bounding_box(&self) -> BoundingBox {
-        let width = self.0.dim().0;
-        let height = self.0.dim().1;
-        let mut min_col = width;
-        let mut max_col = 0;
-        let mut min_row = height;
-        let mut max_row = 0;
-        for r in 0..width {
-            for c in 0..height {
-                if self.0[[r, c]] != ' ' {
-                    min_col = min(min_col, c);
-                    max_col = max(max_col, c);
-                    min_row = min(min_row, r);
-                    max_row = max(max_row, r);
-                }
-            }
-        }
-        BoundingBox {
-            min_col,
-            max_col,
-            min_row,
-            max_row,
-        }
+fn print_front(front: &ParetoFront) {
+    let measure = match front.measure {
+        ParetoMeasure::LongestSide => "Longest side",
+        ParetoMeasure::Perimeter => "Perimeter",
+    };
+    match (front.boards.is_empty(), front.complete) {
+        (false, true) => println!("Smallest boards for each {}:", measure.to_lowercase()),
+        (false, false) => println!("Stopped early, best boards found so far:"),
+        (true, true) => print!("Impossible to solve with these tiles"),
+        (true, false) => print!("Stopped early without finding a solution"),
     }
-
-    fn fits_in_row(
-        &self,
-        word: &str,
-        position: usize,
-        dir: Direction,
-        available_chars: &[char],
-    ) -> bool {
-        let words = self.words_at(position, dir);
-        fits_in_row(word, available_chars, &words)
+    for board in &front.boards {
+        board.grid.print();
+        println!("{}: {}", measure, board.measure);
     }
+}
 
-    fn bounding_box_area(&self) -> usize {
-        self.bounding_box().area()
-    }
+fn solve(args: SolveArgs) {
+    let (dictionary, mut options) = setup(&args.search);
+    let interrupted = cancel_on_interrupt(&mut options);
+    if args.checkpoint.is_some() {
+        options.checkpoint_interval = Some(args.checkpoint_interval);
+    }
+    options.all_optimal = args.all_optimal;
+    options.max_optimal_boards = args.max_boards;
+    options.objective = args.objective();
+    options.partial = args.partial;
+    options.timings |= args.format == Format::Json;
+    let resume = args.resume.as_deref().map(read_checkpoint);
+    let solver = Solver::new(&args.tiles.0, &dictionary, options);
+    log!(Level::Verbose, "{:?}", solver.dictionary().words());
+    let on_event = |event: SolveEvent| match (event, &args.checkpoint) {
+        (SolveEvent::Checkpoint(checkpoint), Some(path)) => write_checkpoint(path, checkpoint),
+        (event, _) => log_event(event),
+    };
 
-    fn valid_bananagrams(&self) -> bool {
-        let bounds = self.bounding_box();
-        let mut words_to_check: Vec<String> = Vec::new();
-        for row in bounds.min_row..bounds.max_row + 1 {
-            words_to_check.extend(
-                self.words_at(row, Direction::Horizontal)
-                    .split_whitespace()
-                    .map(|x| x.to_string()),
-            );
-        }
-        for col in bounds.min_col..bounds.max_col + 1 {
-            words_to_check.extend(
-                self.words_at(col, Direction::Vertical)
-                    .split_whitespace()
-                    .map(|x| x.to_string()),
-            );
+    let start = Instant::now();
+    if let Some(measure) = args.pareto {
+        let front = solver.pareto_front_with(measure.into(), &on_event);
+        if args.format == Format::Json {
+            ParetoReport::new(&args.tiles.0, &args.search.words, &front, start.elapsed()).print();
+        } else {
+            print_front(&front);
         }
-        for word in &words_to_check {
-            if !(*WORD_SET).contains(word) && word.len() > 1 {
-                return false;
+    } else {
+        let boxed = args.width.zip(args.height);
+        let solution = match boxed {
+            Some((width, height)) => {
+                solver.solve_in_box_with(width as usize, height as usize, &on_event)
             }
-        }
-        true
-    }
-
-    fn word_placements_for(
-        &self,
-        word: &str,
-        position: usize,
-        dir: Direction,
-    ) -> Vec<Vec<LetterPlacement>> {
-        let mut result = Vec::new();
-        let bounds = self.bounding_box();
-        let lower = match dir {
-            Direction::Horizontal => bounds.min_col,
-            Direction::Vertical => bounds.min_row,
+            None => match &resume {
+                Some(checkpoint) => solver
+                    .resume_with(checkpoint, &on_event)
+                    .unwrap_or_else(|e| {
+                        eprintln!("can't resume: {}", e);
+                        process::exit(1);
+                    }),
+                None => solver.solve_with(&on_event),
+            },
         };
-        for i in lower - word.len()..lower + 1 {
-            let mut this_result: Vec<LetterPlacement> = Vec::new();
-            let mut connected: bool = false;
-            for j in 0..word.len() {
-                let row = match dir {
-                    Direction::Horizontal => position,
-                    Direction::Vertical => i + j,
-                };
-                let col = match dir {
-                    Direction::Horizontal => i + j,
-                    Direction::Vertical => position,
-                };
-                let letter = word.chars().nth(j).unwrap();
-                match self.get(row, col) {
-                    ' ' => {
-                        this_result.push(LetterPlacement { letter, row, col });
-                        continue;
-                    }
-                    x if x == letter => connected = true,
-                    _ => break,
-                };
-            }
-            if !this_result.is_empty() && connected {
-                result.push(this_result);
-            }
-        }
-        result
-    }
-
-    fn words_at(&self, position: usize, dir: Direction) -> String {
-        let chars = match dir {
-            Direction::Horizontal => self.0.slice(s![position..position + 1, ..]), //row
-            Direction::Vertical => self.0.slice(s![.., position..position + 1]),   //column
+        let stats_level = if interrupted.load(Ordering::Relaxed) {
+            Level::Quiet
+        } else {
+            Level::Verbose
         };
-        String::from_iter(chars)
-    }
-
-    fn insert(&mut self, r: usize, c: usize, val: char) {
-        self.0[[r, c]] = val;
-    }
-
-    fn get(&self, r: usize, c: usize) -> char {
-        self.0[[r, c]]
-    }
-
-    fn clear(&mut self) {
-        for row in 0..self.0.dim().0 {
-            for col in 0..self.0.dim().1 {
-                self.insert(row, col, ' ');
+        if args.format == Format::Json {
+            SolveReport::new(
+                &args.tiles.0,
+                &args.search.words,
+                &solution,
+                start.elapsed(),
+            )
+            .print();
+        } else if let Some((width, height)) = boxed {
+            log_stats(&solution, stats_level);
+            match solution.grid {
+                Some(grid) => {
+                    println!("Fits in {}x{}:", width, height);
+                    grid.print();
+                }
+                None if solution.optimal => print!("No arrangement fits in {}x{}", width, height),
+                None => print!(
+                    "Stopped early without finding a board in {}x{}",
+                    width, height
+                ),
             }
-        }
-    }
-
-    fn place_letter(&mut self, pl: &LetterPlacement) {
-        self.insert(pl.row, pl.col, pl.letter);
-    }
-
-    fn remove_letter(&mut self, pl: &LetterPlacement) {
-        self.insert(pl.row, pl.col, ' ');
-    }
-
-    fn midpoint(&self) -> (usize, usize) {
-        (self.0.dim().0 / 2, self.0.dim().1 / 2)
-    }
-}
-
-struct SolveState {
-    minimum: Option<Grid>,
-    minimum_area: usize,
-    hashed_boards: HashSet<u64>,
-}
-
-//can this word be assembled from these tiles?
-fn can_be_made_with(word: &str, tiles: &[char]) -> bool {
-    let mut tiles = tiles.to_owned();
-    for c in word.chars() {
-        match tiles.iter().position(|x| *x == c) {
-            None => return false,
-            Some(index) => tiles.remove(index),
-        };
-    }
-    true
-}
-
-//can this word be assembled from these tiles
-//given that it also needs to be placed somewhere in row
-fn fits_in_row(word: &str, tiles: &[char], row: &str) -> bool {
-    let mut all_tiles = tiles.to_owned();
-    all_tiles.extend(row.chars().filter(|c| *c != ' ').collect::<Vec<char>>());
-    if !can_be_made_with(word, &all_tiles) {
-        return false;
-    }
-    //trim and pad with spaces
-    let row = format!("{:pad$}{}{:pad$}", "", row.trim(), "", pad = word.len() - 1);
-    let mut row_with_word_inserted = row.clone();
-    'outer: for start_index in 0..row.len() - word.len() + 1 {
-        row_with_word_inserted.replace_range(.., &row);
-        // let mut row_with_word_inserted = row.clone();
-        row_with_word_inserted.replace_range(start_index..start_index + word.len(), word);
-        for (i, c) in row.chars().enumerate() {
-            if c != ' ' && c != row_with_word_inserted.chars().nth(i).unwrap() {
-                continue 'outer;
+        } else {
+            log_stats(&solution, stats_level);
+            match solution.grid {
+                Some(_) if args.all_optimal => {
+                    let kept = solution.optimal_boards.len();
+                    if solution.optimal {
+                        println!("{} minimum solutions:", solution.optimal_count);
+                    } else {
+                        println!(
+                            "Stopped early, {} solutions of the best area found:",
+                            solution.optimal_count
+                        );
+                    }
+                    for board in &solution.optimal_boards {
+                        board.print();
+                    }
+                    if kept < solution.optimal_count {
+                        println!("({} more not shown)", solution.optimal_count - kept);
+                    }
+                }
+                Some(best) if !solution.leftover_tiles.is_empty() => {
+                    if solution.optimal {
+                        println!("Can't use every tile, most used:");
+                    } else {
+                        println!("Stopped early, most tiles used so far:");
+                    }
+                    best.print();
+                    let leftover: String = solution.leftover_tiles.iter().collect();
+                    println!("Tiles left over: {}", leftover);
+                }
+                Some(best) if solver.options().objective.is_some() => {
+                    if solution.optimal {
+                        println!("Best solution:");
+                    } else {
+                        println!("Stopped early, best solution found:");
+                    }
+                    best.print();
+                    let score: Vec<String> = solution
+                        .score
+                        .iter()
+                        .flatten()
+                        .map(|n| n.to_string())
+                        .collect();
+                    println!("Score: {}", score.join(", "));
+                }
+                Some(min) if solution.optimal => {
+                    println!("Minimum solution:");
+                    min.print();
+                }
+                Some(best) => {
+                    println!("Stopped early, best solution found:");
+                    best.print();
+                }
+                None if solution.optimal && args.partial => {
+                    println!("No word can be made with these tiles");
+                    let leftover: String = solution.leftover_tiles.iter().collect();
+                    println!("Tiles left over: {}", leftover);
+                }
+                None if solution.optimal => print!("Impossible to solve with these tiles"),
+                None => print!("Stopped early without finding a solution"),
             }
         }
-        //at this point we know no row characters have been replaced
-        if row_with_word_inserted.split_whitespace().any(|x| x == word) {
-            return true;
-        }
     }
-    false
-}
-
-fn place_word_at(word: &str, c0: usize, r0: usize, dir: Direction) -> Vec<LetterPlacement> {
-    let mut result = Vec::new();
-    for (i, c) in word.chars().enumerate() {
-        result.push(match dir {
-            Direction::Horizontal => LetterPlacement {
-                letter: c,
-                col: c0 + i,
-                row: r0,
-            },
-            Direction::Vertical => LetterPlacement {
-                letter: c,
-                col: c0,
-                row: r0 + i,
-            },
-        });
+    if interrupted.load(Ordering::Relaxed) {
+        process::exit(130);
     }
-    result
 }
 
-fn find_minimum_area_configuration(
-    mystackframe: WordStackFrame,
-    state: &mut SolveState,
-    board: &mut Grid,
-) {
-    //early exit checks
-    let boardhash = board.hash();
-    if state.hashed_boards.contains(&boardhash) {
+fn verify(args: VerifyArgs) {
+    let (dictionary, options) = setup(&args.search);
+    let tiles = &args.tiles.0;
+    let text = fs::read_to_string(&args.board).unwrap_or_else(|e| {
+        eprintln!("couldn't read '{}': {}", args.board.display(), e);
+        process::exit(1);
+    });
+    let board: Grid = text.parse().unwrap_or_else(|e| {
+        eprintln!(
+            "couldn't read the board in '{}': {}",
+            args.board.display(),
+            e
+        );
+        process::exit(1);
+    });
+    board.print();
+    let report = board.validate(&dictionary, Some(tiles));
+    print!("{}", report);
+    if !report.is_valid() {
         return;
     }
-    state.hashed_boards.insert(boardhash);
+    //only a board strictly smaller could beat this one
     let area = board.bounding_box_area();
-    if area >= state.minimum_area {
-        return;
-    }
-    if *PREEMPTIVE_CHECKING && !board.valid_bananagrams() {
-        return;
-    }
-
-    let remaining_tiles = &mystackframe.remaining_tiles;
-    //Base Case: we are out of tiles so we found a solution
-    if remaining_tiles.is_empty() {
-        if board.valid_bananagrams() && (state.minimum.is_none() || area < state.minimum_area) {
-            state.minimum = Some(board.clone());
-            state.minimum_area = area;
-            println!("New Smallest Solution Found!");
-            board.print();
+    let solver = Solver::new(tiles, &dictionary, options);
+    let solution = solver.solve_within_area(area - 1);
+    match solution.grid {
+        Some(smaller) => {
+            println!("Not optimal, this board is smaller:");
+            smaller.print();
         }
-        return;
+        None if solution.optimal => println!("Optimal: no board of these tiles is smaller"),
+        None => println!("Stopped early, couldn't tell if a smaller board exists"),
     }
+}
 
-    //Base Case: we have an empty board and should place a first word
-    if mystackframe.recursion_depth == 0 {
-        for word in &*ALL_WORDS {
-            println!("{}", word);
-            let midpoint = board.midpoint();
-            let placement = place_word_at(word, midpoint.0, midpoint.1, Direction::Horizontal);
-            let mut tiles = mystackframe.remaining_tiles.clone();
-            //actually place tiles we are assigned
-            for ltr in &placement {
-                board.place_letter(ltr);
-                let index = tiles.iter().position(|x| *x == ltr.letter).unwrap();
-                tiles.remove(index);
-            }
-            find_minimum_area_configuration(
-                WordStackFrame {
-                    remaining_tiles: tiles,
-                    available_words: mystackframe.available_words.clone(),
-                    recursion_depth: 1,
-                },
-                state,
-                board,
-            );
-            //remove tiles we are assigned
-            for ltr in &placement {
-                board.remove_letter(ltr);
-            }
+fn bench(args: BenchArgs) {
+    let (dictionary, options) = setup(&args.search);
+    for hand in &args.hands {
+        let word: String = hand.0.iter().collect();
+        let mut times = Vec::new();
+        let mut area = None;
+        for _ in 0..args.runs {
+            let start = Instant::now();
+            let solver = Solver::new(&hand.0, &dictionary, options.clone());
+            area = solver.solve().area;
+            times.push(start.elapsed());
         }
-        return;
+        let best = times.iter().min().unwrap();
+        let mean = times.iter().sum::<std::time::Duration>() / args.runs;
+        let area = area.map_or("none".to_string(), |a| a.to_string());
+        println!(
+            "{}: area {}, best {:.3?}, mean {:.3?} over {} runs",
+            word, area, best, mean, args.runs
+        );
     }
+}
 
-    let bounds = board.bounding_box();
-    let mut available_words = mystackframe.available_words;
-    for row in bounds.min_row..bounds.max_row + 1 {
-        let prev_words = {
-            if let Some(ws) = available_words.get(&(Direction::Horizontal, row)) {
-                ws
-            } else {
-                &*ALL_WORDS
-            }
-        };
-        let current_words = prev_words
-            .iter()
-            .filter(|w| board.fits_in_row(w, row, Direction::Horizontal, &remaining_tiles))
-            .map(|w| w.to_string())
-            .collect();
-        available_words.insert((Direction::Horizontal, row), current_words);
-    }
-    for col in bounds.min_col..bounds.max_col + 1 {
-        let prev_words = {
-            if let Some(ws) = available_words.get(&(Direction::Vertical, col)) {
-                ws
-            } else {
-                &*ALL_WORDS
-            }
-        };
-        let current_words = prev_words
-            .iter()
-            .filter(|w| board.fits_in_row(w, col, Direction::Vertical, &remaining_tiles))
-            .map(|w| w.to_string())
-            .collect();
-        available_words.insert((Direction::Vertical, col), current_words);
-    }
+fn generate(args: GenerateArgs) {
+    let mut bag = args.seed.map_or_else(Bag::new, Bag::seeded);
+    let hand: String = bag.draw(args.count as usize).into_iter().collect();
+    println!("{}", hand);
+}
 
-    for row in bounds.min_row..bounds.max_row + 1 {
-        for word in available_words.get(&(Direction::Horizontal, row)).unwrap() {
-            let word_placements = board.word_placements_for(word, row, Direction::Horizontal);
-            for placement in word_placements {
-                //check if word can be made
-                let tilesplaced: String = placement.iter().map(|lp| lp.letter).collect();
-                if !can_be_made_with(&tilesplaced, &remaining_tiles) {
-                    continue;
-                }
-                let mut tiles = mystackframe.remaining_tiles.clone();
-                //actually place tiles we are assigned
-                for ltr in &placement {
-                    board.place_letter(ltr);
-                    let index = tiles.iter().position(|x| *x == ltr.letter).unwrap();
-                    tiles.remove(index);
-                }
-                //recurse
-                find_minimum_area_configuration(
-                    WordStackFrame {
-                        remaining_tiles: tiles.clone(),
-                        available_words: available_words.clone(),
-                        recursion_depth: &mystackframe.recursion_depth + 1,
-                    },
-                    state,
-                    board,
-                );
-                //remove tiles we are assigned
-                for ltr in &placement {
-                    board.remove_letter(ltr);
-                }
+fn play(args: PlayArgs) {
+    let (dictionary, options) = setup(&args.search);
+    let mut bag = args.seed.map_or_else(Bag::new, Bag::seeded);
+    let mut hand = bag.draw(args.hand as usize);
+    loop {
+        println!("Hand: {}", hand.iter().collect::<String>());
+        //any board will do, the smallest would take too long as the hand grows
+        let solver = Solver::new(&hand, &dictionary, options.clone());
+        let solution = solver.solve_within_area(hand.len() * hand.len());
+        match solution.grid {
+            Some(board) => board.print(),
+            None if solution.optimal => {
+                println!("Stuck: no board uses all of these tiles");
+                return;
             }
-        }
-    }
-    for col in bounds.min_col..bounds.max_col + 1 {
-        for word in available_words.get(&(Direction::Vertical, col)).unwrap() {
-            let word_placements = board.word_placements_for(word, col, Direction::Vertical);
-            for placement in word_placements {
-                //check if word can be made
-                let tilesplaced: String = placement.iter().map(|lp| lp.letter).collect();
-                if !can_be_made_with(&tilesplaced, &remaining_tiles) {
-                    continue;
-                }
-                let mut tiles = mystackframe.remaining_tiles.clone();
-                //actually place tiles we are assigned
-                for ltr in &placement {
-                    board.place_letter(ltr);
-                    let index = tiles.iter().position(|x| *x == ltr.letter).unwrap();
-                    tiles.remove(index);
-                }
-                //recurse
-                find_minimum_area_configuration(
-                    WordStackFrame {
-                        remaining_tiles: tiles.clone(),
-                        available_words: available_words.clone(),
-                        recursion_depth: &mystackframe.recursion_depth + 1,
-                    },
-                    state,
-                    board,
-                );
-                //remove tiles we are assigned
-                for ltr in &placement {
-                    board.remove_letter(ltr);
-                }
+            None => {
+                println!("Stopped early without finding a board for this hand");
+                return;
             }
         }
+        if bag.is_empty() {
+            println!("Bananas! The bag is empty");
+            return;
+        }
+        let peel = bag.draw(1);
+        println!("Peel: {}", peel[0]);
+        hand.extend(peel);
     }
 }
 
-#[test]
-fn bounding_box() {
-    let mut grid = Grid(Array2::from_elem((10, 10), ' '));
-    grid.insert(5, 5, 'o');
-    let bounds = grid.bounding_box();
-    assert_eq!(bounds.min_row, 5);
-    assert_eq!(bounds.max_row, 5);
-    assert_eq!(bounds.min_col, 5);
-    assert_eq!(bounds.max_col, 5);
-    grid.insert(7, 6, 'o');
-    let bounds = grid.bounding_box();
-    assert_eq!(bounds.min_row, 5);
-    assert_eq!(bounds.max_row, 7);
-    assert_eq!(bounds.min_col, 5);
-    assert_eq!(bounds.max_col, 6);
-    grid.insert(3, 4, 'o');
-    let bounds = grid.bounding_box();
-    assert_eq!(bounds.min_row, 3);
-    assert_eq!(bounds.max_row, 7);
-    assert_eq!(bounds.min_col, 4);
-    assert_eq!(bounds.max_col, 6);
-    assert_eq!(grid.bounding_box_area(), 15);
-}
-
-#[test]
-fn fits_in_row_1() {
-    let tiles = "ab".chars().collect::<Vec<char>>();
-    let row = "   a c ";
-    assert!(fits_in_row("abc", &tiles, row));
-    assert!(!fits_in_row("abcb", &tiles, row));
-    assert!(fits_in_row("cab", &tiles, row));
-    assert!(!fits_in_row("abac", &tiles, row));
-}
-
-#[test]
-fn fits_in_row_2() {
-    let tiles = "ab".chars().collect::<Vec<char>>();
-    let row = "a a";
-    let good_words = vec!["aa", "aba", "a"];
-    let bad_words = vec!["abaca", "cabac", "abbabababababbaa"];
-    for word in good_words {
-        assert!(fits_in_row(word, &tiles, row));
+fn count(args: CountArgs) {
+    let (dictionary, mut options) = setup(&args.search);
+    let interrupted = cancel_on_interrupt(&mut options);
+    let solver = Solver::new(&args.tiles.0, &dictionary, options);
+    let count = solver.count_with(&log_event);
+    if count.complete {
+        println!("{} boards", count.total);
+    } else {
+        println!("Stopped early, {} boards so far", count.total);
     }
-    for word in bad_words {
-        assert!(!fits_in_row(word, &tiles, row));
+    if args.by_area {
+        for (area, boards) in &count.by_area {
+            println!("Area {}: {}", area, boards);
+        }
+    }
+    if interrupted.load(Ordering::Relaxed) {
+        process::exit(130);
     }
-}
-
-#[test]
-fn hash_grids() {
-    let mut board = Grid(Array2::from_elem((5, 5), ' '));
-    let empty_hash = board.hash();
-    board.insert(1, 1, 'h');
-    assert!(board.hash() != empty_hash);
-    board.insert(1, 1, ' ');
-    assert!(board.hash() == empty_hash);
-    let board2 = Grid(Array2::from_elem((5, 5), ' '));
-    assert!(board2.hash() == empty_hash);
-}
-
-#[test]
-fn hash_offset() {
-    let mut board = Grid(Array2::from_elem((5, 5), ' '));
-    let empty_hash = board.hash();
-    board.insert(1, 1, 'h');
-    board.insert(1, 2, 'i');
-    board.insert(2, 1, 'i');
-    let hi_hash = board.hash();
-    assert!(board.hash() != empty_hash);
-    board.clear();
-    assert!(board.hash() == empty_hash);
-    board.insert(2, 2, 'h');
-    board.insert(2, 3, 'i');
-    board.insert(3, 2, 'i');
-    assert!(board.hash() == hi_hash);
-}
-
-#[test]
-fn hash_transpose() {
-    let mut board = Grid(Array2::from_elem((5, 5), ' '));
-    let empty_hash = board.hash();
-    board.insert(1, 1, 'h');
-    board.insert(1, 2, 'i');
-    board.insert(2, 1, 'e');
-    board.insert(3, 1, 'y');
-    let hi_hey_hash = board.hash();
-    assert!(board.hash() != empty_hash);
-    board.clear();
-    assert!(board.hash() == empty_hash);
-    board.insert(1, 1, 'h');
-    board.insert(1, 2, 'e');
-    board.insert(1, 3, 'y');
-    board.insert(2, 1, 'i');
-    assert!(board.hash() == hi_hey_hash);
-    board.insert(1, 1, ' ');
-    assert!(board.hash() != hi_hey_hash);
 }
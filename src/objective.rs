@@ -0,0 +1,203 @@
+//what makes one board better than another. the solver minimizes a score, comparing
+//scores element by element, so an objective to maximize scores the negative
+use crate::grid::{BoundingBox, Grid};
+use crate::solver::area_lower_bound;
+use std::{cmp::max, fmt, sync::Arc};
+
+pub type Score = Vec<i64>;
+
+pub trait Objective: fmt::Debug + Send + Sync {
+    //the score of a finished board, lower is better
+    fn score(&self, board: &Grid) -> Score;
+    //no board made by adding the remaining tiles to this one scores less than this
+    fn lower_bound(&self, board: &Grid, remaining_tiles: &[char]) -> Score;
+    //how it is written on the command line and in checkpoints
+    fn name(&self) -> String;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Area;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Perimeter;
+
+//the smallest difference between width and height
+#[derive(Debug, Clone, Copy)]
+pub struct MostSquare;
+
+#[derive(Debug, Clone, Copy)]
+pub struct FewestWords;
+
+#[derive(Debug, Clone, Copy)]
+pub struct MostWords;
+
+//the board with the longest word on it
+#[derive(Debug, Clone, Copy)]
+pub struct LongestWord;
+
+//the board with the most tiles on it, for boards that don't use them all
+#[derive(Debug, Clone, Copy)]
+pub struct MostTiles;
+
+//the first objective, then the next to break ties, and so on
+#[derive(Debug, Clone)]
+pub struct Lexicographic(pub Vec<Arc<dyn Objective>>);
+
+fn sides(bounds: &BoundingBox) -> (i64, i64) {
+    (bounds.width() as i64, bounds.height() as i64)
+}
+
+impl Objective for Area {
+    fn score(&self, board: &Grid) -> Score {
+        vec![board.bounding_box_area() as i64]
+    }
+
+    fn lower_bound(&self, board: &Grid, remaining_tiles: &[char]) -> Score {
+        vec![area_lower_bound(board, remaining_tiles) as i64]
+    }
+
+    fn name(&self) -> String {
+        "area".to_string()
+    }
+}
+
+//the bounding box only grows, so neither can the perimeter
+impl Objective for Perimeter {
+    fn score(&self, board: &Grid) -> Score {
+        let (width, height) = sides(&board.bounding_box());
+        vec![2 * (width + height)]
+    }
+
+    fn lower_bound(&self, board: &Grid, _: &[char]) -> Score {
+        self.score(board)
+    }
+
+    fn name(&self) -> String {
+        "perimeter".to_string()
+    }
+}
+
+//each tile added can lengthen the shorter side by at most one
+impl Objective for MostSquare {
+    fn score(&self, board: &Grid) -> Score {
+        let (width, height) = sides(&board.bounding_box());
+        vec![(width - height).abs()]
+    }
+
+    fn lower_bound(&self, board: &Grid, remaining_tiles: &[char]) -> Score {
+        let difference = self.score(board)[0];
+        vec![max(difference - remaining_tiles.len() as i64, 0)]
+    }
+
+    fn name(&self) -> String {
+        "most-square".to_string()
+    }
+}
+
+//words can be joined into longer ones, so all that's sure is there will be one, unless
+//there's only a tile, which makes no word at all
+impl Objective for FewestWords {
+    fn score(&self, board: &Grid) -> Score {
+        vec![board.placed_words().len() as i64]
+    }
+
+    fn lower_bound(&self, board: &Grid, remaining_tiles: &[char]) -> Score {
+        vec![(board.tile_count() + remaining_tiles.len() >= 2) as i64]
+    }
+
+    fn name(&self) -> String {
+        "fewest-words".to_string()
+    }
+}
+
+//a tile starts at most one new word across and one down
+impl Objective for MostWords {
+    fn score(&self, board: &Grid) -> Score {
+        vec![-(board.placed_words().len() as i64)]
+    }
+
+    fn lower_bound(&self, board: &Grid, remaining_tiles: &[char]) -> Score {
+        vec![self.score(board)[0] - 2 * remaining_tiles.len() as i64]
+    }
+
+    fn name(&self) -> String {
+        "most-words".to_string()
+    }
+}
+
+//no word is longer than the side of the box it ends up in
+impl Objective for LongestWord {
+    fn score(&self, board: &Grid) -> Score {
+        let longest = board
+            .placed_words()
+            .iter()
+            .map(|placed| placed.word.len())
+            .max()
+            .unwrap_or(0);
+        vec![-(longest as i64)]
+    }
+
+    fn lower_bound(&self, board: &Grid, remaining_tiles: &[char]) -> Score {
+        let (width, height) = sides(&board.bounding_box());
+        vec![-(max(width, height) + remaining_tiles.len() as i64)]
+    }
+
+    fn name(&self) -> String {
+        "longest-word".to_string()
+    }
+}
+
+//at best every remaining tile is placed
+impl Objective for MostTiles {
+    fn score(&self, board: &Grid) -> Score {
+        vec![-(board.tile_count() as i64)]
+    }
+
+    fn lower_bound(&self, board: &Grid, remaining_tiles: &[char]) -> Score {
+        vec![self.score(board)[0] - remaining_tiles.len() as i64]
+    }
+
+    fn name(&self) -> String {
+        "most-tiles".to_string()
+    }
+}
+
+//bounds on every part bound the whole, as scores compare element by element
+impl Objective for Lexicographic {
+    fn score(&self, board: &Grid) -> Score {
+        self.0.iter().flat_map(|o| o.score(board)).collect()
+    }
+
+    fn lower_bound(&self, board: &Grid, remaining_tiles: &[char]) -> Score {
+        self.0
+            .iter()
+            .flat_map(|o| o.lower_bound(board, remaining_tiles))
+            .collect()
+    }
+
+    fn name(&self) -> String {
+        let names: Vec<String> = self.0.iter().map(|o| o.name()).collect();
+        names.join(",")
+    }
+}
+
+#[test]
+fn scores_boards() {
+    let board = Grid::from_rows(&["or ", "mel"]);
+    assert_eq!(Area.score(&board), vec![6]);
+    assert_eq!(Perimeter.score(&board), vec![10]);
+    assert_eq!(MostSquare.score(&board), vec![1]);
+    //or, mel, om and re
+    assert_eq!(FewestWords.score(&board), vec![4]);
+    assert_eq!(MostWords.score(&board), vec![-4]);
+    assert_eq!(LongestWord.score(&board), vec![-3]);
+    assert_eq!(MostTiles.lower_bound(&board, &['a']), vec![-6]);
+    let tile = Grid::from_rows(&["a"]);
+    assert_eq!(FewestWords.score(&tile), vec![0]);
+    assert_eq!(FewestWords.lower_bound(&tile, &[]), vec![0]);
+    assert_eq!(FewestWords.lower_bound(&tile, &['b']), vec![1]);
+    let both = Lexicographic(vec![Arc::new(Perimeter), Arc::new(LongestWord)]);
+    assert_eq!(both.score(&board), vec![10, -3]);
+    assert_eq!(both.name(), "perimeter,longest-word");
+    assert_eq!(both.lower_bound(&board, &['a', 'b']), vec![10, -5]);
+}
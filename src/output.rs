@@ -0,0 +1,213 @@
+//machine readable results, for --format json
+use bananagrams::{
+    Grid, ParetoFront, ParetoMeasure, PlacedWord, SearchStats, Solution, TableStats,
+};
+use serde::Serialize;
+use std::{path::Path, time::Duration};
+
+#[derive(Debug, Serialize)]
+pub struct SolveReport {
+    pub tiles: String,
+    pub dictionary: String,
+    //the best board, cropped to its bounding box so coordinates start at 0
+    pub grid: Option<BoardReport>,
+    pub area: Option<usize>,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub optimal: bool,
+    //the board's score by the objective, see objective.rs, or its area
+    pub score: Option<Vec<i64>>,
+    //tiles not on the board, with --partial
+    pub leftover_tiles: String,
+    //with --all-optimal, the boards tied for the smallest area and how many there are
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub optimal_boards: Option<Vec<BoardReport>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub optimal_count: Option<usize>,
+    pub elapsed_seconds: f64,
+    pub stats: StatsReport,
+    pub table: TableStats,
+}
+
+//SearchStats with its timings in seconds
+#[derive(Debug, Serialize)]
+pub struct StatsReport {
+    pub nodes: u64,
+    pub nodes_per_depth: Vec<u64>,
+    pub table_hits: u64,
+    pub bound_prunes: u64,
+    pub box_prunes: u64,
+    pub validity_prunes: u64,
+    pub complete_boards: u64,
+    pub horizontal_candidates: u64,
+    pub vertical_candidates: u64,
+    pub candidates_per_row: Vec<u64>,
+    pub candidates_per_col: Vec<u64>,
+    pub seconds: PhaseSeconds,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PhaseSeconds {
+    pub index: f64,
+    pub table: f64,
+    pub bound: f64,
+    pub validation: f64,
+    pub move_generation: f64,
+}
+
+impl From<&SearchStats> for StatsReport {
+    fn from(stats: &SearchStats) -> StatsReport {
+        let timings = &stats.timings;
+        StatsReport {
+            nodes: stats.nodes,
+            nodes_per_depth: stats.nodes_per_depth.clone(),
+            table_hits: stats.table_hits,
+            bound_prunes: stats.bound_prunes,
+            box_prunes: stats.box_prunes,
+            validity_prunes: stats.validity_prunes,
+            complete_boards: stats.complete_boards,
+            horizontal_candidates: stats.horizontal_candidates,
+            vertical_candidates: stats.vertical_candidates,
+            candidates_per_row: stats.candidates_per_row.clone(),
+            candidates_per_col: stats.candidates_per_col.clone(),
+            seconds: PhaseSeconds {
+                index: timings.index.as_secs_f64(),
+                table: timings.table.as_secs_f64(),
+                bound: timings.bound.as_secs_f64(),
+                validation: timings.validation.as_secs_f64(),
+                move_generation: timings.move_generation.as_secs_f64(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BoardReport {
+    pub rows: Vec<String>,
+    pub placed_words: Vec<PlacedWord>,
+}
+
+impl BoardReport {
+    fn new(cropped: &Grid) -> BoardReport {
+        BoardReport {
+            rows: cropped.rows(),
+            placed_words: cropped.placed_words(),
+        }
+    }
+}
+
+impl SolveReport {
+    pub fn new(
+        tiles: &[char],
+        dictionary: &Path,
+        solution: &Solution,
+        elapsed: Duration,
+    ) -> SolveReport {
+        let all_optimal = solution.optimal_count > 0;
+        let cropped = solution.grid.as_ref().map(Grid::cropped);
+        let size = cropped.as_ref().map(|grid| {
+            let bounds = grid.bounding_box();
+            (bounds.width(), bounds.height())
+        });
+        SolveReport {
+            tiles: tiles.iter().collect(),
+            dictionary: dictionary.display().to_string(),
+            grid: cropped.as_ref().map(BoardReport::new),
+            area: solution.area,
+            width: size.map(|(width, _)| width),
+            height: size.map(|(_, height)| height),
+            optimal: solution.optimal,
+            score: solution.score.clone(),
+            leftover_tiles: solution.leftover_tiles.iter().collect(),
+            optimal_boards: if all_optimal {
+                Some(
+                    solution
+                        .optimal_boards
+                        .iter()
+                        .map(|board| BoardReport::new(&board.cropped()))
+                        .collect(),
+                )
+            } else {
+                None
+            },
+            optimal_count: if all_optimal {
+                Some(solution.optimal_count)
+            } else {
+                None
+            },
+            elapsed_seconds: elapsed.as_secs_f64(),
+            stats: StatsReport::from(&solution.stats),
+            table: solution.table_stats,
+        }
+    }
+
+    pub fn print(&self) {
+        println!("{}", serde_json::to_string_pretty(self).unwrap());
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParetoReport {
+    pub tiles: String,
+    pub dictionary: String,
+    //what area is traded off against, "longest_side" or "perimeter"
+    pub measure: &'static str,
+    //smallest area first
+    pub boards: Vec<ParetoBoardReport>,
+    //false if a limit stopped the search before the front was proven
+    pub complete: bool,
+    pub elapsed_seconds: f64,
+    pub stats: StatsReport,
+    pub table: TableStats,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParetoBoardReport {
+    pub area: usize,
+    pub measure: usize,
+    pub width: usize,
+    pub height: usize,
+    #[serde(flatten)]
+    pub board: BoardReport,
+}
+
+impl ParetoReport {
+    pub fn new(
+        tiles: &[char],
+        dictionary: &Path,
+        front: &ParetoFront,
+        elapsed: Duration,
+    ) -> ParetoReport {
+        ParetoReport {
+            tiles: tiles.iter().collect(),
+            dictionary: dictionary.display().to_string(),
+            measure: match front.measure {
+                ParetoMeasure::LongestSide => "longest_side",
+                ParetoMeasure::Perimeter => "perimeter",
+            },
+            boards: front
+                .boards
+                .iter()
+                .map(|board| {
+                    let cropped = board.grid.cropped();
+                    let bounds = cropped.bounding_box();
+                    ParetoBoardReport {
+                        area: board.area,
+                        measure: board.measure,
+                        width: bounds.width(),
+                        height: bounds.height(),
+                        board: BoardReport::new(&cropped),
+                    }
+                })
+                .collect(),
+            complete: front.complete,
+            elapsed_seconds: elapsed.as_secs_f64(),
+            stats: StatsReport::from(&front.stats),
+            table: front.table_stats,
+        }
+    }
+
+    pub fn print(&self) {
+        println!("{}", serde_json::to_string_pretty(self).unwrap());
+    }
+}
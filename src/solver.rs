@@ -0,0 +1,1841 @@
+use crate::checkpoint::{hash_words, Checkpoint, CheckpointError};
+use crate::dictionary::Dictionary;
+use crate::gaddag::Gaddag;
+use crate::grid::{
+    can_be_made_with, place_word_at, BoardKey, BoundingBox, Direction, Grid, LetterPlacement,
+};
+use crate::objective::{Area, Lexicographic, MostTiles, Objective, Score};
+use crate::stats::SearchStats;
+use crate::transposition::{ReplacementPolicy, SharedTable, TableStats};
+use crate::trie::Trie;
+use rand::prelude::*;
+use std::{
+    cell::RefCell,
+    cmp::{max, min},
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+//order in which candidate words are tried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordOrder {
+    Dictionary,
+    ShortestFirst,
+    LongestFirst,
+}
+
+//how the next words to place are found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveGenerator {
+    //words that fit each row and column of the bounding box, slid in from its lower edge
+    Rows,
+    //every valid placement through the squares next to the board's letters
+    Anchored,
+}
+
+//what a Pareto front trades area off against, see Solver::pareto_front
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParetoMeasure {
+    //the larger of width and height
+    LongestSide,
+    Perimeter,
+}
+
+impl ParetoMeasure {
+    //boards only grow, so this is also a lower bound for every board grown from one
+    pub fn of(self, bounds: &BoundingBox) -> usize {
+        match self {
+            ParetoMeasure::LongestSide => max(bounds.width(), bounds.height()),
+            ParetoMeasure::Perimeter => 2 * (bounds.width() + bounds.height()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SolveOptions {
+    //check if the board is valid at every step, not just at the end
+    pub preemptive_checking: bool,
+    //shuffle the word list before ordering it
+    pub shuffle: bool,
+    pub word_order: WordOrder,
+    pub min_word_length: usize,
+    pub max_word_length: usize,
+    pub move_generator: MoveGenerator,
+    //bytes the record of already searched boards may take up
+    pub table_memory: usize,
+    pub replacement: ReplacementPolicy,
+    //first words are handed out to this many threads
+    pub threads: usize,
+    //prune boards whose area_lower_bound can't beat the minimum, rather than their area
+    pub lower_bound: bool,
+    //look for a board within a target area, raising the target until one is found,
+    //instead of starting from any board and shrinking it
+    pub iterative_deepening: bool,
+    //send a Visit event for every board the search reaches, which slows it down
+    pub visit_events: bool,
+    //time each phase of the search into its stats, which reads the clock at every board
+    pub timings: bool,
+    //stop searching after this long or this many nodes and return the best board so far
+    pub time_limit: Option<Duration>,
+    pub node_limit: Option<u64>,
+    //set this from another thread to stop the same way
+    pub cancel: Option<Arc<AtomicBool>>,
+    //send a Checkpoint event this often, and when the search is stopped early
+    pub checkpoint_interval: Option<Duration>,
+    //keep every board as small as the minimum, not just the first, counting boards
+    //that are the same moved or transposed once; only the first max_optimal_boards
+    //are kept, but all of them are counted
+    pub all_optimal: bool,
+    pub max_optimal_boards: usize,
+    //what to minimize instead of area, pruning on its lower bound whatever lower_bound
+    //says; iterative_deepening and all_optimal only work with the area. None is the
+    //area, searched faster than with the Area objective
+    pub objective: Option<Arc<dyn Objective>>,
+    //if not every tile can be used, place as many as possible: every valid board is a
+    //candidate, compared on tiles used first and then on the objective. only solve and
+    //resume look for such boards, every other search still uses all the tiles
+    pub partial: bool,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        SolveOptions {
+            preemptive_checking: false,
+            shuffle: false,
+            word_order: WordOrder::Dictionary,
+            min_word_length: 0,
+            max_word_length: usize::MAX,
+            move_generator: MoveGenerator::Anchored,
+            table_memory: 64 << 20,
+            replacement: ReplacementPolicy::DepthPreferred,
+            threads: 1,
+            lower_bound: true,
+            iterative_deepening: false,
+            visit_events: false,
+            timings: false,
+            time_limit: None,
+            node_limit: None,
+            cancel: None,
+            checkpoint_interval: None,
+            all_optimal: false,
+            max_optimal_boards: 100,
+            objective: None,
+            partial: false,
+        }
+    }
+}
+
+//things that happen during a search which a caller may want to report
+pub enum SolveEvent<'a> {
+    //iterative deepening is starting a search for boards within this area
+    TargetArea(usize),
+    FirstWord(&'a str),
+    NewMinimum(&'a Grid),
+    //a board the search has reached, depth words in, only sent if visit_events is set
+    Visit {
+        depth: usize,
+        board: &'a Grid,
+        remaining_tiles: &'a [char],
+    },
+    //progress a search could be resumed from, see checkpoint_interval
+    Checkpoint(&'a Checkpoint),
+}
+
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub grid: Option<Grid>,
+    pub area: Option<usize>,
+    pub table_stats: TableStats,
+    pub stats: SearchStats,
+    //the search ran to the end, so grid is as small as a board can be, or there is
+    //no board at all; false if a limit stopped it, or the method only looks for any board
+    pub optimal: bool,
+    //with all_optimal, every board with the minimum area up to max_optimal_boards,
+    //and how many there are in all
+    pub optimal_boards: Vec<Grid>,
+    pub optimal_count: usize,
+    //the grid's score by the objective, or its area
+    pub score: Option<Score>,
+    //tiles not on the grid, only ever some with partial, and then all of them if
+    //there is no grid
+    pub leftover_tiles: Vec<char>,
+}
+
+//how many distinct boards a hand makes, from Solver::count
+#[derive(Debug, Clone)]
+pub struct BoardCount {
+    pub total: usize,
+    //the number of boards with each bounding box area
+    pub by_area: BTreeMap<usize, usize>,
+    pub table_stats: TableStats,
+    pub stats: SearchStats,
+    //false if a limit stopped the search, so there may be more boards
+    pub complete: bool,
+}
+
+//boards no other board beats on both area and a ParetoMeasure, from Solver::pareto_front
+#[derive(Debug, Clone)]
+pub struct ParetoFront {
+    pub measure: ParetoMeasure,
+    //smallest area first, so largest measure first
+    pub boards: Vec<ParetoBoard>,
+    pub table_stats: TableStats,
+    pub stats: SearchStats,
+    //false if a limit stopped the search, so some boards may be beaten by ones not found
+    pub complete: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParetoBoard {
+    pub grid: Grid,
+    pub area: usize,
+    pub measure: usize,
+}
+
+pub struct Solver {
+    tiles: Vec<char>,
+    dictionary: Dictionary,
+    index: WordIndex,
+    //how long building the index took
+    index_time: Duration,
+    options: SolveOptions,
+    //the options' objective, after partial has put tiles used ahead of it
+    objective: Option<Arc<dyn Objective>>,
+}
+
+//the structure the move generator walks to find words
+enum WordIndex {
+    Trie(Trie),
+    Gaddag(Gaddag),
+}
+
+impl Solver {
+    //keeps only the words that can be made with these tiles, in the order the options ask for
+    pub fn new(tiles: &[char], dictionary: &Dictionary, options: SolveOptions) -> Solver {
+        let mut words: Vec<&String> = dictionary
+            .words()
+            .iter()
+            .filter(|word| {
+                word.len() >= options.min_word_length && word.len() <= options.max_word_length
+            })
+            .filter(|word| can_be_made_with(word, tiles))
+            .collect();
+        if options.shuffle {
+            words.shuffle(&mut thread_rng());
+        }
+        match options.word_order {
+            WordOrder::Dictionary => {}
+            WordOrder::ShortestFirst => words.sort_by_key(|a| a.len()),
+            WordOrder::LongestFirst => {
+                words.sort_by_key(|a| a.len());
+                words.reverse();
+            }
+        }
+        let dictionary: Dictionary = words.into_iter().cloned().collect();
+        let started = Instant::now();
+        let index = match options.move_generator {
+            MoveGenerator::Rows => WordIndex::Trie(Trie::new(dictionary.words())),
+            MoveGenerator::Anchored => WordIndex::Gaddag(Gaddag::new(dictionary.words())),
+        };
+        let objective = if options.partial {
+            let then = options.objective.clone().unwrap_or_else(|| Arc::new(Area));
+            let both: Arc<dyn Objective> = Arc::new(Lexicographic(vec![Arc::new(MostTiles), then]));
+            Some(both)
+        } else {
+            options.objective.clone()
+        };
+        Solver {
+            tiles: tiles.to_vec(),
+            index,
+            index_time: started.elapsed(),
+            dictionary,
+            options,
+            objective,
+        }
+    }
+
+    pub fn tiles(&self) -> &[char] {
+        &self.tiles
+    }
+
+    //the words this solver will draw from, already filtered for its tiles
+    pub fn dictionary(&self) -> &Dictionary {
+        &self.dictionary
+    }
+
+    pub fn options(&self) -> &SolveOptions {
+        &self.options
+    }
+
+    pub fn solve(&self) -> Solution {
+        self.solve_with(&|_| {})
+    }
+
+    //with several threads the board is the same as with one: ties go to the earliest
+    //first word, and the table never hides a board from an earlier first word
+    pub fn solve_with(&self, on_event: &(dyn Fn(SolveEvent) + Sync)) -> Solution {
+        self.run(on_event, None)
+    }
+
+    //carry on from a checkpoint made by a solver with the same tiles, words and options
+    pub fn resume_with(
+        &self,
+        checkpoint: &Checkpoint,
+        on_event: &(dyn Fn(SolveEvent) + Sync),
+    ) -> Result<Solution, CheckpointError> {
+        if checkpoint.tiles != self.tiles.iter().collect::<String>() {
+            return Err(CheckpointError::DifferentTiles {
+                saved: checkpoint.tiles.clone(),
+            });
+        }
+        let words = self.dictionary.words();
+        if checkpoint.word_count != words.len() || checkpoint.words_hash != hash_words(words) {
+            return Err(CheckpointError::DifferentWords);
+        }
+        let objective = self.objective.as_ref().map(|o| o.name());
+        if checkpoint.objective != objective {
+            return Err(CheckpointError::DifferentObjective {
+                saved: checkpoint.objective.clone(),
+            });
+        }
+        if checkpoint.target_area.is_some() != self.iterative() {
+            return Err(CheckpointError::DifferentSearch);
+        }
+        //the boards tied with the minimum aren't saved
+        if self.options.all_optimal {
+            return Err(CheckpointError::AllOptimal);
+        }
+        Ok(self.run(on_event, Some(checkpoint)))
+    }
+
+    fn run(
+        &self,
+        on_event: &(dyn Fn(SolveEvent) + Sync),
+        mut resume: Option<&Checkpoint>,
+    ) -> Solution {
+        let board_dim = self.tiles.len() * 2;
+        let largest_area = (board_dim * board_dim).saturating_sub(1);
+        let outcome = if self.iterative() {
+            //each target is the smallest area that was pruned by the last one, so the
+            //first board found is as small as any can be
+            //the limits cover every target together, and a search stopped by one
+            //has no board to return
+            let mut target = resume
+                .and_then(|checkpoint| checkpoint.target_area)
+                .unwrap_or_else(|| rectangle_lower_bound(0, 0, self.tiles.len()));
+            let mut limits = self.limits();
+            let mut table_stats = TableStats::default();
+            let mut stats = SearchStats::default();
+            loop {
+                on_event(SolveEvent::TargetArea(target));
+                let mut outcome = self.search(
+                    on_event,
+                    Target {
+                        largest_area: target,
+                        stop_at_first: !self.options.all_optimal,
+                        fit_in: None,
+                        limits,
+                        checkpoints: true,
+                        count: false,
+                        pareto: None,
+                        partial: self.options.partial,
+                    },
+                    resume.take(),
+                );
+                table_stats += outcome.table_stats;
+                outcome.table_stats = table_stats;
+                stats += &outcome.stats;
+                outcome.stats = stats.clone();
+                if outcome.minimum.is_some()
+                    || outcome.next_area > largest_area
+                    || outcome.limit_reached
+                {
+                    break outcome;
+                }
+                //stats are the total over every target so far
+                limits.node_limit = self
+                    .options
+                    .node_limit
+                    .map(|limit| limit.saturating_sub(stats.nodes));
+                target = outcome.next_area;
+            }
+        } else {
+            self.search(
+                on_event,
+                Target {
+                    largest_area,
+                    stop_at_first: false,
+                    fit_in: None,
+                    limits: self.limits(),
+                    checkpoints: true,
+                    count: false,
+                    pareto: None,
+                    partial: self.options.partial,
+                },
+                resume,
+            )
+        };
+        outcome.into_solution(self)
+    }
+
+    //any board with at most this area; None means there is none
+    pub fn solve_within_area(&self, largest_area: usize) -> Solution {
+        let outcome = self.search(
+            &|_| {},
+            Target {
+                largest_area,
+                stop_at_first: true,
+                fit_in: None,
+                limits: self.limits(),
+                checkpoints: false,
+                count: false,
+                pareto: None,
+                partial: false,
+            },
+            None,
+        );
+        let mut solution = outcome.into_solution(self);
+        solution.optimal &= solution.grid.is_none();
+        solution
+    }
+
+    //any board that fits inside a width by height rectangle, turned as given;
+    //None means none fits
+    pub fn solve_in_box(&self, width: usize, height: usize) -> Solution {
+        self.solve_in_box_with(width, height, &|_| {})
+    }
+
+    pub fn solve_in_box_with(
+        &self,
+        width: usize,
+        height: usize,
+        on_event: &(dyn Fn(SolveEvent) + Sync),
+    ) -> Solution {
+        let mut outcome = self.search(
+            on_event,
+            Target {
+                largest_area: width.saturating_mul(height),
+                stop_at_first: true,
+                fit_in: Some((width, height)),
+                limits: self.limits(),
+                checkpoints: false,
+                count: false,
+                pareto: None,
+                partial: false,
+            },
+            None,
+        );
+        //the table can't tell a board from its transpose, so either may have been found
+        outcome.minimum = outcome.minimum.map(|grid| {
+            let bounds = grid.bounding_box();
+            if bounds.width() <= width && bounds.height() <= height {
+                grid
+            } else {
+                grid.transpose()
+            }
+        });
+        let mut solution = outcome.into_solution(self);
+        solution.optimal &= solution.grid.is_none();
+        solution
+    }
+
+    //every valid board using all the tiles, a board moved or transposed counting once;
+    //always placed with the anchored generator, as the rows one misses some boards
+    pub fn count(&self) -> BoardCount {
+        self.count_with(&|_| {})
+    }
+
+    pub fn count_with(&self, on_event: &(dyn Fn(SolveEvent) + Sync)) -> BoardCount {
+        if let WordIndex::Trie(_) = self.index {
+            let options = SolveOptions {
+                move_generator: MoveGenerator::Anchored,
+                ..self.options.clone()
+            };
+            return Solver::new(&self.tiles, &self.dictionary, options).count_with(on_event);
+        }
+        let outcome = self.search(
+            on_event,
+            Target {
+                //unused, nothing is pruned for its area
+                largest_area: 0,
+                stop_at_first: false,
+                fit_in: None,
+                limits: self.limits(),
+                checkpoints: false,
+                count: true,
+                pareto: None,
+                partial: false,
+            },
+            None,
+        );
+        let mut by_area = BTreeMap::new();
+        let boards = outcome.counted.unwrap_or_default();
+        for area in boards.values() {
+            *by_area.entry(*area).or_insert(0) += 1;
+        }
+        let mut stats = outcome.stats;
+        stats.timings.index = self.index_time;
+        BoardCount {
+            total: boards.len(),
+            by_area,
+            table_stats: outcome.table_stats,
+            stats,
+            complete: !outcome.limit_reached,
+        }
+    }
+
+    //every board that is smallest for its measure, one for each area and measure
+    //pair; minimum area alone tends to give long thin boards
+    pub fn pareto_front(&self, measure: ParetoMeasure) -> ParetoFront {
+        self.pareto_front_with(measure, &|_| {})
+    }
+
+    pub fn pareto_front_with(
+        &self,
+        measure: ParetoMeasure,
+        on_event: &(dyn Fn(SolveEvent) + Sync),
+    ) -> ParetoFront {
+        let outcome = self.search(
+            on_event,
+            Target {
+                largest_area: 0,
+                stop_at_first: false,
+                fit_in: None,
+                limits: self.limits(),
+                checkpoints: false,
+                count: false,
+                pareto: Some(measure),
+                partial: false,
+            },
+            None,
+        );
+        let mut boards = outcome.front.unwrap_or_default();
+        boards.sort_by_key(|board| board.area);
+        let mut stats = outcome.stats;
+        stats.timings.index = self.index_time;
+        ParetoFront {
+            measure,
+            boards,
+            table_stats: outcome.table_stats,
+            stats,
+            complete: !outcome.limit_reached,
+        }
+    }
+
+    fn iterative(&self) -> bool {
+        self.options.iterative_deepening && self.objective.is_none()
+    }
+
+    //the options' limits, counting time from now
+    fn limits(&self) -> Limits {
+        Limits {
+            deadline: self.options.time_limit.map(|limit| Instant::now() + limit),
+            node_limit: self.options.node_limit,
+        }
+    }
+
+    fn search(
+        &self,
+        on_event: &(dyn Fn(SolveEvent) + Sync),
+        mut target: Target,
+        resume: Option<&Checkpoint>,
+    ) -> SearchOutcome {
+        let board_dim = self.tiles.len() * 2;
+        //no board is as big as the grid it's placed on, and the bound only has room
+        //for an area of 32 bits
+        target.largest_area = min(target.largest_area, board_dim * board_dim);
+        let threads = self.options.threads.max(1);
+        let mut minimum = None;
+        //when collecting ties the bound's area is the largest still wanted rather than
+        //one more than it, see within_bound
+        let objective = &self.objective;
+        let ties = self.options.all_optimal && !target.stop_at_first && objective.is_none();
+        let mut bound = pack_bound(target.largest_area + !ties as usize, 0);
+        let count = target.count;
+        let pareto = target.pareto.is_some();
+        let mut best_score = None;
+        let mut completed = Vec::new();
+        if let Some(checkpoint) = resume {
+            if let Some(rows) = &checkpoint.best {
+                let best = Grid::from_rows(rows);
+                match objective {
+                    Some(objective) => {
+                        best_score = Some((objective.score(&best), checkpoint.best_first_word))
+                    }
+                    None => {
+                        bound = pack_bound(best.bounding_box_area(), checkpoint.best_first_word)
+                    }
+                }
+                minimum = Some(best);
+            }
+            completed = checkpoint.completed_first_words.clone();
+        }
+        let state = SolveState {
+            solver: self,
+            on_event,
+            minimum: Mutex::new(minimum),
+            bound: AtomicU64::new(bound),
+            best_score: Mutex::new(best_score),
+            hashed_boards: SharedTable::new(
+                self.options.table_memory,
+                self.options.replacement,
+                if threads == 1 { 1 } else { threads * 8 },
+            ),
+            next_first_word: AtomicUsize::new(0),
+            target,
+            finished: AtomicBool::new(false),
+            next_area: AtomicUsize::new(usize::MAX),
+            stats: Mutex::new(SearchStats::default()),
+            nodes: AtomicU64::new(0),
+            limit_reached: AtomicBool::new(false),
+            completed: Mutex::new(completed),
+            last_checkpoint: Mutex::new(Instant::now()),
+            ties: if ties {
+                Some(Mutex::new(Ties::default()))
+            } else {
+                None
+            },
+            counted: if count {
+                Some(Mutex::new(HashMap::new()))
+            } else {
+                None
+            },
+            front: if pareto {
+                Some(Mutex::new(Vec::new()))
+            } else {
+                None
+            },
+        };
+        if threads == 1 {
+            search_first_words(&state, board_dim);
+        } else {
+            thread::scope(|scope| {
+                for _ in 0..threads {
+                    scope.spawn(|| search_first_words(&state, board_dim));
+                }
+            });
+        }
+        if state.limit_reached.load(Ordering::Relaxed) {
+            state.send_checkpoint();
+        }
+        SearchOutcome {
+            table_stats: state.hashed_boards.stats(),
+            minimum: state.minimum.into_inner().unwrap(),
+            next_area: state.next_area.into_inner(),
+            stats: state.stats.into_inner().unwrap(),
+            limit_reached: state.limit_reached.into_inner(),
+            ties: state.ties.map(|ties| ties.into_inner().unwrap()),
+            counted: state.counted.map(|counted| counted.into_inner().unwrap()),
+            front: state.front.map(|front| front.into_inner().unwrap()),
+            partial: state.target.partial,
+        }
+    }
+}
+
+//what one search is looking for
+struct Target {
+    //only boards no bigger than this
+    largest_area: usize,
+    //end the search as soon as any board is recorded
+    stop_at_first: bool,
+    //only boards that fit a width by height rectangle one way round or the other
+    fit_in: Option<(usize, usize)>,
+    limits: Limits,
+    //send Checkpoint events, for searches that can be resumed
+    checkpoints: bool,
+    //record every board instead of the smallest, with nothing pruned for its area
+    count: bool,
+    //record the boards no other beats on both area and this measure, pruning boards
+    //that can't do better than one of them on either
+    pareto: Option<ParetoMeasure>,
+    //also record boards that leave tiles out, see SolveOptions::partial; only when
+    //looking for the best board, as other searches want boards using every tile
+    partial: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    deadline: Option<Instant>,
+    node_limit: Option<u64>,
+}
+
+struct SearchOutcome {
+    minimum: Option<Grid>,
+    //smallest bound of a board pruned for being over the starting area
+    next_area: usize,
+    table_stats: TableStats,
+    stats: SearchStats,
+    limit_reached: bool,
+    ties: Option<Ties>,
+    counted: Option<HashMap<BoardKey, usize>>,
+    front: Option<Vec<ParetoBoard>>,
+    partial: bool,
+}
+
+//distinct boards with the smallest area found so far
+#[derive(Debug, Default)]
+struct Ties {
+    keys: HashSet<BoardKey>,
+    boards: Vec<Grid>,
+}
+
+impl Ties {
+    fn add(&mut self, board: &Grid, max_boards: usize) {
+        if self.keys.insert(board.canonical_key()) && self.boards.len() < max_boards {
+            self.boards.push(board.clone());
+        }
+    }
+}
+
+impl SearchOutcome {
+    fn into_solution(mut self, solver: &Solver) -> Solution {
+        self.stats.timings.index = solver.index_time;
+        let ties = self.ties.unwrap_or_default();
+        let score = self.minimum.as_ref().map(|grid| match &solver.objective {
+            Some(objective) => objective.score(grid),
+            None => vec![grid.bounding_box_area() as i64],
+        });
+        let leftover_tiles = match &self.minimum {
+            Some(grid) => {
+                grid.validate(&solver.dictionary, Some(&solver.tiles))
+                    .unused_tiles
+            }
+            None if self.partial => solver.tiles.clone(),
+            None => Vec::new(),
+        };
+        Solution {
+            score,
+            leftover_tiles,
+            optimal_count: ties.keys.len(),
+            optimal_boards: ties.boards,
+            area: self.minimum.as_ref().map(|grid| grid.bounding_box_area()),
+            grid: self.minimum,
+            table_stats: self.table_stats,
+            stats: self.stats,
+            optimal: !self.limit_reached,
+        }
+    }
+}
+
+//the smallest area any board made by adding the remaining tiles to this one could have:
+//its bounding box can only grow, and has to end up with room for every tile
+pub fn area_lower_bound(board: &Grid, remaining_tiles: &[char]) -> usize {
+    let bounds = board.bounding_box();
+    let mut tiles = remaining_tiles.len();
+    for row in bounds.min_row..bounds.max_row + 1 {
+        for col in bounds.min_col..bounds.max_col + 1 {
+            if board.get(row, col) != ' ' {
+                tiles += 1;
+            }
+        }
+    }
+    rectangle_lower_bound(bounds.width(), bounds.height(), tiles)
+}
+
+//smallest w * h holding this many tiles with w and h at least width and height
+fn rectangle_lower_bound(width: usize, height: usize, tiles: usize) -> usize {
+    if tiles == 0 {
+        return width * height;
+    }
+    let mut best = usize::MAX;
+    for w in max(width, 1)..max(width, tiles) + 1 {
+        let h = max(height, tiles.div_ceil(w));
+        best = min(best, w * h);
+        //any wider is only bigger
+        if h == height {
+            break;
+        }
+    }
+    best
+}
+
+#[derive(Debug, Clone)]
+struct WordStackFrame {
+    remaining_tiles: Vec<char>,
+    recursion_depth: usize,
+}
+
+//shared by every thread working on one search
+struct SolveState<'a> {
+    solver: &'a Solver,
+    on_event: &'a (dyn Fn(SolveEvent) + Sync),
+    minimum: Mutex<Option<Grid>>,
+    //area of the minimum so far and the first word it came from, see pack_bound
+    bound: AtomicU64,
+    //the same for the objective's score, when there is one
+    best_score: Mutex<Option<(Score, usize)>>,
+    hashed_boards: SharedTable,
+    next_first_word: AtomicUsize,
+    target: Target,
+    finished: AtomicBool,
+    next_area: AtomicUsize,
+    stats: Mutex<SearchStats>,
+    //only counted while there is a node limit
+    nodes: AtomicU64,
+    limit_reached: AtomicBool,
+    //first words searched under all the way, including before resuming
+    completed: Mutex<Vec<usize>>,
+    last_checkpoint: Mutex<Instant>,
+    //only when looking for every optimal board
+    ties: Option<Mutex<Ties>>,
+    //only when counting, every board found by its key with its area
+    counted: Option<Mutex<HashMap<BoardKey, usize>>>,
+    //only when finding a Pareto front, the boards on it so far
+    front: Option<Mutex<Vec<ParetoBoard>>>,
+}
+
+impl<'a> SolveState<'a> {
+    fn send_checkpoint(&self) {
+        if !self.target.checkpoints || self.solver.options.checkpoint_interval.is_none() {
+            return;
+        }
+        let checkpoint = {
+            //the bound only changes with the minimum locked
+            let minimum = self.minimum.lock().unwrap();
+            let best_first_word = match &*self.best_score.lock().unwrap() {
+                Some((_, first_word)) => *first_word,
+                None => (self.bound.load(Ordering::Acquire) & 0xffff_ffff) as usize,
+            };
+            let mut completed = self.completed.lock().unwrap().clone();
+            completed.sort_unstable();
+            let words = self.solver.dictionary.words();
+            Checkpoint {
+                tiles: self.solver.tiles.iter().collect(),
+                word_count: words.len(),
+                words_hash: hash_words(words),
+                target_area: if self.solver.iterative() {
+                    Some(self.target.largest_area)
+                } else {
+                    None
+                },
+                best: minimum.as_ref().map(|grid| grid.cropped().rows()),
+                best_first_word,
+                completed_first_words: completed,
+                objective: self.solver.objective.as_ref().map(|o| o.name()),
+            }
+        };
+        *self.last_checkpoint.lock().unwrap() = Instant::now();
+        (self.on_event)(SolveEvent::Checkpoint(&checkpoint));
+    }
+
+    //send a checkpoint if it has been checkpoint_interval since the last one
+    fn checkpoint_if_due(&self) {
+        if let Some(interval) = self.solver.options.checkpoint_interval {
+            if self.last_checkpoint.lock().unwrap().elapsed() >= interval {
+                self.send_checkpoint();
+            }
+        }
+    }
+}
+
+//one thread's view of the search: the index of the first word it is working under
+struct Worker<'a> {
+    state: &'a SolveState<'a>,
+    first_word: usize,
+    //counted here and added to the state's total once the first word is done
+    stats: RefCell<SearchStats>,
+}
+
+//is some board on the front at least as good on both counts
+fn dominated(front: &[ParetoBoard], area: usize, measure: usize) -> bool {
+    front
+        .iter()
+        .any(|board| board.area <= area && board.measure <= measure)
+}
+
+//area first, then first word index, so comparing packed bounds prefers the
+//smaller area and then the board a single thread would have found first
+fn elapsed(started: Option<Instant>) -> Duration {
+    started.map_or(Duration::ZERO, |started| started.elapsed())
+}
+
+fn pack_bound(area: usize, first_word: usize) -> u64 {
+    ((area as u64) << 32) | first_word as u64
+}
+
+impl<'a> Worker<'a> {
+    //can a board with this area still beat the minimum, or tie with it when
+    //collecting every optimal board
+    fn within_bound(&self, area: usize, bounds: &BoundingBox) -> bool {
+        if self.state.counted.is_some() {
+            return true;
+        }
+        if let (Some(front), Some(measure)) = (&self.state.front, self.state.target.pareto) {
+            let measure = measure.of(bounds);
+            return !dominated(&front.lock().unwrap(), area, measure);
+        }
+        let bound = self.state.bound.load(Ordering::Acquire);
+        if self.state.ties.is_some() {
+            if area <= (bound >> 32) as usize {
+                return true;
+            }
+        } else if pack_bound(area, self.first_word) < bound {
+            return true;
+        }
+        if area >= (bound >> 32) as usize {
+            self.state.next_area.fetch_min(area, Ordering::Relaxed);
+        }
+        false
+    }
+
+    //with an objective, would this board beat the best score
+    fn improves(&self, board: &Grid) -> bool {
+        let objective = match &self.state.solver.objective {
+            Some(objective) => objective,
+            None => return false,
+        };
+        let score = objective.score(board);
+        match &*self.state.best_score.lock().unwrap() {
+            Some((best, first_word)) => (&score, self.first_word) < (best, *first_word),
+            None => true,
+        }
+    }
+
+    //with an objective, can a board grown from this one still beat the best score;
+    //ties go to the earlier first word as with the area
+    fn within_objective(&self, board: &Grid, remaining_tiles: &[char]) -> bool {
+        let objective = match &self.state.solver.objective {
+            Some(objective) => objective,
+            None => return true,
+        };
+        let bound = objective.lower_bound(board, remaining_tiles);
+        match &*self.state.best_score.lock().unwrap() {
+            Some((best, first_word)) => (&bound, self.first_word) < (best, *first_word),
+            None => true,
+        }
+    }
+
+    //has the search run out of time or nodes or been cancelled; if so every
+    //thread is told to stop
+    fn over_limit(&self) -> bool {
+        let state = self.state;
+        let limits = state.target.limits;
+        let cancel = &state.solver.options.cancel;
+        let over = cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+            || limits
+                .node_limit
+                .is_some_and(|limit| state.nodes.fetch_add(1, Ordering::Relaxed) >= limit)
+            || limits
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline);
+        if over {
+            state.limit_reached.store(true, Ordering::Relaxed);
+            state.finished.store(true, Ordering::Release);
+        }
+        over
+    }
+
+    //when a phase started, if the search is timing them
+    fn start_timer(&self) -> Option<Instant> {
+        self.state.solver.options.timings.then(Instant::now)
+    }
+
+    //is every word on the board in the dictionary, timed as validation
+    fn valid(&self, board: &Grid) -> bool {
+        let started = self.start_timer();
+        let valid = board.valid_bananagrams(&self.state.solver.dictionary);
+        self.stats.borrow_mut().timings.validation += elapsed(started);
+        valid
+    }
+
+    //placements generated along one line, and the time since generating them started
+    fn count_candidates(
+        &self,
+        placements: &[Vec<LetterPlacement>],
+        dir: Direction,
+        line: usize,
+        started: Option<Instant>,
+    ) {
+        let mut stats = self.stats.borrow_mut();
+        stats.count_candidates(dir, line, placements.len() as u64);
+        stats.timings.move_generation += elapsed(started);
+    }
+
+    fn record_minimum(&self, board: &Grid, area: usize) {
+        if let Some(counted) = &self.state.counted {
+            counted.lock().unwrap().insert(board.canonical_key(), area);
+            return;
+        }
+        if let (Some(front), Some(measure)) = (&self.state.front, self.state.target.pareto) {
+            let measure = measure.of(&board.bounding_box());
+            let mut front = front.lock().unwrap();
+            //another thread may have added a board as good since the bound was checked
+            if dominated(&front, area, measure) {
+                return;
+            }
+            front.retain(|other| other.area < area || other.measure < measure);
+            front.push(ParetoBoard {
+                grid: board.clone(),
+                area,
+                measure,
+            });
+            return;
+        }
+        let mut minimum = self.state.minimum.lock().unwrap();
+        if let Some(objective) = &self.state.solver.objective {
+            let score = objective.score(board);
+            let mut best = self.state.best_score.lock().unwrap();
+            let better = match &*best {
+                Some((best, first_word)) => (&score, self.first_word) < (best, *first_word),
+                None => true,
+            };
+            if better {
+                *minimum = Some(board.clone());
+                *best = Some((score, self.first_word));
+                (self.state.on_event)(SolveEvent::NewMinimum(board));
+                if self.state.target.stop_at_first {
+                    self.state.finished.store(true, Ordering::Release);
+                }
+            }
+            return;
+        }
+        if let Some(ties) = &self.state.ties {
+            let mut ties = ties.lock().unwrap();
+            let best = (self.state.bound.load(Ordering::Acquire) >> 32) as usize;
+            if minimum.is_none() || area < best {
+                *minimum = Some(board.clone());
+                self.state
+                    .bound
+                    .store(pack_bound(area, self.first_word), Ordering::Release);
+                *ties = Ties::default();
+                (self.state.on_event)(SolveEvent::NewMinimum(board));
+            }
+            if area == (self.state.bound.load(Ordering::Acquire) >> 32) as usize {
+                ties.add(board, self.state.solver.options.max_optimal_boards);
+            }
+            return;
+        }
+        let bound = pack_bound(area, self.first_word);
+        if bound < self.state.bound.load(Ordering::Acquire) {
+            *minimum = Some(board.clone());
+            self.state.bound.store(bound, Ordering::Release);
+            (self.state.on_event)(SolveEvent::NewMinimum(board));
+            if self.state.target.stop_at_first {
+                self.state.finished.store(true, Ordering::Release);
+            }
+        }
+    }
+}
+
+//how many nodes a worker searches between seeing whether a checkpoint is due
+const CHECKPOINT_CHECK_NODES: u64 = 1024;
+
+//Base Case: we have an empty board and should place a first word
+//each thread takes the next first word nobody has started on until they run out
+fn search_first_words(state: &SolveState, board_dim: usize) {
+    let mut board = Grid::new(board_dim, board_dim);
+    let words = state.solver.dictionary.words();
+    while !state.finished.load(Ordering::Acquire) {
+        let first_word = state.next_first_word.fetch_add(1, Ordering::Relaxed);
+        let word = match words.get(first_word) {
+            Some(word) => word,
+            None => return,
+        };
+        if state.completed.lock().unwrap().contains(&first_word) {
+            continue;
+        }
+        (state.on_event)(SolveEvent::FirstWord(word));
+        //centred, so every board that grows from it has room on all sides and boards
+        //the table treats as the same really do have the same boards after them
+        let midpoint = board.midpoint();
+        let c0 = midpoint.1 - word.chars().count() / 2;
+        let placement = place_word_at(word, c0, midpoint.0, Direction::Horizontal);
+        let mut tiles = state.solver.tiles.clone();
+        //actually place tiles we are assigned
+        for ltr in &placement {
+            board.place_letter(ltr);
+            let index = tiles.iter().position(|x| *x == ltr.letter).unwrap();
+            tiles.remove(index);
+        }
+        let worker = Worker {
+            state,
+            first_word,
+            stats: RefCell::new(SearchStats::default()),
+        };
+        find_minimum_area_configuration(
+            WordStackFrame {
+                remaining_tiles: tiles,
+                recursion_depth: 1,
+            },
+            &worker,
+            &mut board,
+        );
+        *state.stats.lock().unwrap() += &worker.stats.borrow();
+        //a limit may have cut this first word short
+        if !state.limit_reached.load(Ordering::Relaxed) {
+            state.completed.lock().unwrap().push(first_word);
+            state.checkpoint_if_due();
+        }
+        //remove tiles we are assigned
+        for ltr in &placement {
+            board.remove_letter(ltr);
+        }
+    }
+}
+
+//place the tiles of each placement in turn, recursing on the resulting board
+fn try_placements(
+    placements: Vec<Vec<LetterPlacement>>,
+    mystackframe: &WordStackFrame,
+    worker: &Worker,
+    board: &mut Grid,
+) {
+    for placement in placements {
+        //check if word can be made
+        let tilesplaced: String = placement.iter().map(|lp| lp.letter).collect();
+        if !can_be_made_with(&tilesplaced, &mystackframe.remaining_tiles) {
+            continue;
+        }
+        let mut tiles = mystackframe.remaining_tiles.clone();
+        //actually place tiles we are assigned
+        for ltr in &placement {
+            board.place_letter(ltr);
+            let index = tiles.iter().position(|x| *x == ltr.letter).unwrap();
+            tiles.remove(index);
+        }
+        //recurse
+        find_minimum_area_configuration(
+            WordStackFrame {
+                remaining_tiles: tiles,
+                recursion_depth: mystackframe.recursion_depth + 1,
+            },
+            worker,
+            board,
+        );
+        //remove tiles we are assigned
+        for ltr in &placement {
+            board.remove_letter(ltr);
+        }
+    }
+}
+
+fn find_minimum_area_configuration(
+    mystackframe: WordStackFrame,
+    worker: &Worker,
+    board: &mut Grid,
+) {
+    //early exit checks
+    let state = worker.state;
+    if state.finished.load(Ordering::Acquire) || worker.over_limit() {
+        return;
+    }
+    let nodes = {
+        let mut stats = worker.stats.borrow_mut();
+        stats.count_node(mystackframe.recursion_depth);
+        stats.nodes
+    };
+    //a single first word can take hours, so don't wait for it to finish
+    if nodes % CHECKPOINT_CHECK_NODES == 0 {
+        state.checkpoint_if_due();
+    }
+    let started = worker.start_timer();
+    let seen = state.hashed_boards.check_and_insert(
+        board.canonical_key(),
+        mystackframe.recursion_depth,
+        worker.first_word,
+    );
+    {
+        let mut stats = worker.stats.borrow_mut();
+        stats.timings.table += elapsed(started);
+        if seen {
+            stats.table_hits += 1;
+            return;
+        }
+    }
+    let solver = state.solver;
+    if solver.options.visit_events {
+        (state.on_event)(SolveEvent::Visit {
+            depth: mystackframe.recursion_depth,
+            board,
+            remaining_tiles: &mystackframe.remaining_tiles,
+        });
+    }
+    let started = worker.start_timer();
+    let area = board.bounding_box_area();
+    let bound = if solver.options.lower_bound {
+        area_lower_bound(board, &mystackframe.remaining_tiles)
+    } else {
+        area
+    };
+    let within_bound = worker.within_bound(bound, &board.bounding_box())
+        && worker.within_objective(board, &mystackframe.remaining_tiles);
+    {
+        let mut stats = worker.stats.borrow_mut();
+        stats.timings.bound += elapsed(started);
+        if !within_bound {
+            stats.bound_prunes += 1;
+            return;
+        }
+    }
+    if let Some((width, height)) = state.target.fit_in {
+        let bounds = board.bounding_box();
+        let (w, h) = (bounds.width(), bounds.height());
+        let fits = (w <= width && h <= height) || (w <= height && h <= width);
+        if !fits {
+            worker.stats.borrow_mut().box_prunes += 1;
+            return;
+        }
+    }
+    if solver.options.preemptive_checking && !worker.valid(board) {
+        worker.stats.borrow_mut().validity_prunes += 1;
+        return;
+    }
+
+    let remaining_tiles = &mystackframe.remaining_tiles;
+    //Base Case: we are out of tiles so we found a solution
+    if remaining_tiles.is_empty() {
+        worker.stats.borrow_mut().complete_boards += 1;
+        if worker.valid(board) {
+            worker.record_minimum(board, area);
+            state.checkpoint_if_due();
+        }
+        return;
+    }
+    //a board leaving tiles out may be the best there is, scored first as that's cheaper
+    if state.target.partial && worker.improves(board) && worker.valid(board) {
+        worker.record_minimum(board, area);
+        state.checkpoint_if_due();
+    }
+
+    match &solver.index {
+        WordIndex::Trie(trie) => {
+            //generate the words that fit in each row and column from the index
+            let bounds = board.bounding_box();
+            let lines = (bounds.min_row..bounds.max_row + 1)
+                .map(|row| (row, Direction::Horizontal))
+                .chain((bounds.min_col..bounds.max_col + 1).map(|col| (col, Direction::Vertical)));
+            for (line, dir) in lines {
+                let started = worker.start_timer();
+                let line_words = board.words_at(line, dir);
+                let mut placements = Vec::new();
+                for word in trie.words_fitting(&line_words, remaining_tiles) {
+                    placements.extend(board.word_placements_for(&word, line, dir));
+                }
+                worker.count_candidates(&placements, dir, line, started);
+                try_placements(placements, &mystackframe, worker, board);
+            }
+        }
+        WordIndex::Gaddag(gaddag) => {
+            let started = worker.start_timer();
+            let placements = gaddag.placements(board, remaining_tiles);
+            {
+                let mut stats = worker.stats.borrow_mut();
+                for placement in &placements {
+                    //a single tile is generated as horizontal
+                    let first = &placement[0];
+                    if placement.iter().any(|l| l.row != first.row) {
+                        stats.count_candidates(Direction::Vertical, first.col, 1);
+                    } else {
+                        stats.count_candidates(Direction::Horizontal, first.row, 1);
+                    }
+                }
+                stats.timings.move_generation += elapsed(started);
+            }
+            try_placements(placements, &mystackframe, worker, board);
+        }
+    }
+}
+
+#[test]
+fn generators_agree_on_minimum() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    for hand in &["banana", "hello", "gate"] {
+        let tiles: Vec<char> = hand.chars().collect();
+        let areas: Vec<Option<usize>> = [MoveGenerator::Rows, MoveGenerator::Anchored]
+            .iter()
+            .map(|&move_generator| {
+                let options = SolveOptions {
+                    move_generator,
+                    ..SolveOptions::default()
+                };
+                Solver::new(&tiles, &dictionary, options).solve().area
+            })
+            .collect();
+        assert_eq!(areas[0], areas[1], "{}", hand);
+    }
+}
+
+#[test]
+fn rectangle_bounds() {
+    assert_eq!(rectangle_lower_bound(3, 1, 8), 8);
+    assert_eq!(rectangle_lower_bound(3, 1, 9), 9);
+    assert_eq!(rectangle_lower_bound(5, 2, 7), 10);
+    assert_eq!(rectangle_lower_bound(5, 1, 7), 7);
+    assert_eq!(rectangle_lower_bound(4, 4, 7), 16);
+    assert_eq!(rectangle_lower_bound(0, 0, 6), 6);
+}
+
+#[test]
+fn lower_bound_never_cuts_the_optimum() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    for hand in &["lorem", "banana", "quiet", "gizmos", "ordeal"] {
+        let tiles: Vec<char> = hand.chars().collect();
+        let unbounded = SolveOptions {
+            lower_bound: false,
+            ..SolveOptions::default()
+        };
+        let without = Solver::new(&tiles, &dictionary, unbounded).solve();
+        let with = Solver::new(&tiles, &dictionary, SolveOptions::default()).solve();
+        assert_eq!(without.area, with.area, "{}", hand);
+        //and is exact once every tile is down
+        if let Some(grid) = with.grid {
+            assert_eq!(area_lower_bound(&grid, &[]), grid.bounding_box_area());
+        }
+    }
+}
+
+#[test]
+fn iterative_deepening_finds_same_minimum() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    for hand in &["lorem", "banana", "quiet", "ordeal", "gizmos"] {
+        let tiles: Vec<char> = hand.chars().collect();
+        let shrinking = Solver::new(&tiles, &dictionary, SolveOptions::default()).solve();
+        let options = SolveOptions {
+            iterative_deepening: true,
+            ..SolveOptions::default()
+        };
+        let targets = Mutex::new(Vec::new());
+        let deepening = Solver::new(&tiles, &dictionary, options).solve_with(&|event| {
+            if let SolveEvent::TargetArea(area) = event {
+                targets.lock().unwrap().push(area);
+            }
+        });
+        assert_eq!(shrinking.area, deepening.area, "{}", hand);
+        //only the last target had a board in it, if any did
+        let targets = targets.into_inner().unwrap();
+        assert!(targets.windows(2).all(|w| w[0] < w[1]));
+        if deepening.area.is_some() {
+            assert_eq!(targets.last().cloned(), deepening.area);
+        }
+    }
+}
+
+#[test]
+fn solves_in_box() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let tiles: Vec<char> = "loremipsum".chars().collect();
+    let solver = Solver::new(&tiles, &dictionary, SolveOptions::default());
+    //the smallest board has area 12, so 3x3 can't work
+    assert!(solver.solve_in_box(3, 3).grid.is_none());
+    //areas too big for the bound, or to multiply out at all
+    let huge = [(65536, 65536), (usize::MAX, 2), (usize::MAX, usize::MAX)];
+    for &(width, height) in [(10, 10), (3, 5), (5, 3)].iter().chain(&huge) {
+        let grid = solver.solve_in_box(width, height).grid.unwrap();
+        let bounds = grid.bounding_box();
+        assert!(bounds.width() <= width && bounds.height() <= height);
+        assert!(grid.valid_bananagrams(solver.dictionary()));
+    }
+}
+
+#[test]
+fn solves_within_area() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let tiles: Vec<char> = "loremipsum".chars().collect();
+    let solver = Solver::new(&tiles, &dictionary, SolveOptions::default());
+    assert!(solver.solve_within_area(11).grid.is_none());
+    let within = solver.solve_within_area(20);
+    assert!(within.area.unwrap() <= 20);
+    assert!(within.grid.unwrap().valid_bananagrams(solver.dictionary()));
+    assert!(solver.solve_within_area(usize::MAX).grid.is_some());
+}
+
+#[test]
+fn threads_find_same_minimum() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    for hand in &["lorem", "banana", "quiet", "loremip"] {
+        let tiles: Vec<char> = hand.chars().collect();
+        let single = Solver::new(&tiles, &dictionary, SolveOptions::default()).solve();
+        for threads in 2..5 {
+            let options = SolveOptions {
+                threads,
+                ..SolveOptions::default()
+            };
+            let solver = Solver::new(&tiles, &dictionary, options);
+            let parallel = solver.solve();
+            assert_eq!(single.area, parallel.area, "{}", hand);
+            //the very same board, not just one as small
+            assert_eq!(
+                single.grid.as_ref().map(Grid::rows),
+                parallel.grid.as_ref().map(Grid::rows),
+                "{} with {} threads",
+                hand,
+                threads
+            );
+        }
+    }
+}
+
+#[test]
+fn small_table_finds_same_minimum() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let tiles: Vec<char> = "lorem".chars().collect();
+    let unbounded = Solver::new(&tiles, &dictionary, SolveOptions::default()).solve();
+    for &replacement in &[
+        ReplacementPolicy::DepthPreferred,
+        ReplacementPolicy::LeastRecentlyUsed,
+    ] {
+        let options = SolveOptions {
+            table_memory: 1024,
+            replacement,
+            ..SolveOptions::default()
+        };
+        let bounded = Solver::new(&tiles, &dictionary, options).solve();
+        assert_eq!(bounded.area, unbounded.area);
+        assert!(bounded.table_stats.evictions > 0);
+    }
+}
+
+#[test]
+fn solves_small_hand() {
+    let dictionary = Dictionary::from_slice(&["hi", "hey", "he", "ye", "yeh", "hive"]);
+    let tiles: Vec<char> = "hiey".chars().collect();
+    let solver = Solver::new(&tiles, &dictionary, SolveOptions::default());
+    assert!(!solver.dictionary().contains("hive"));
+    let solution = solver.solve();
+    let grid = solution.grid.unwrap();
+    assert!(grid.valid_bananagrams(solver.dictionary()));
+    assert_eq!(solution.area, Some(grid.bounding_box_area()));
+    //every board looked up in the table was a node
+    let table = solution.table_stats;
+    let stats = &solution.stats;
+    assert_eq!(stats.nodes, table.hits + table.misses);
+    assert_eq!(stats.table_hits, table.hits);
+    assert_eq!(stats.nodes_per_depth.iter().sum::<u64>(), stats.nodes);
+    assert!(stats.complete_boards > 0);
+}
+
+#[test]
+fn visits_every_new_board() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let tiles: Vec<char> = "lorem".chars().collect();
+    let options = SolveOptions {
+        visit_events: true,
+        ..SolveOptions::default()
+    };
+    let visits = Mutex::new(Vec::new());
+    let solution = Solver::new(&tiles, &dictionary, options).solve_with(&|event| {
+        if let SolveEvent::Visit {
+            depth,
+            board,
+            remaining_tiles,
+        } = event
+        {
+            let placed: usize = board
+                .rows()
+                .iter()
+                .map(|r| r.trim().chars().filter(|c| *c != ' ').count())
+                .sum();
+            assert_eq!(placed + remaining_tiles.len(), tiles.len());
+            visits.lock().unwrap().push(depth);
+        }
+    });
+    let visits = visits.into_inner().unwrap();
+    assert_eq!(visits.len() as u64, solution.table_stats.misses);
+    assert!(visits.iter().all(|&depth| depth >= 1));
+}
+
+#[test]
+fn limits_return_the_best_board_so_far() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let tiles: Vec<char> = "loremipsum".chars().collect();
+    let full = Solver::new(&tiles, &dictionary, SolveOptions::default()).solve();
+    assert!(full.optimal);
+    let options = SolveOptions {
+        node_limit: Some(500),
+        ..SolveOptions::default()
+    };
+    let limited = Solver::new(&tiles, &dictionary, options).solve();
+    assert!(!limited.optimal);
+    assert!(limited.stats.nodes <= 500);
+    if let Some(area) = limited.area {
+        assert!(area >= full.area.unwrap());
+    }
+    let options = SolveOptions {
+        time_limit: Some(Duration::from_secs(0)),
+        iterative_deepening: true,
+        ..SolveOptions::default()
+    };
+    let stopped = Solver::new(&tiles, &dictionary, options).solve();
+    assert!(!stopped.optimal);
+    assert!(stopped.grid.is_none());
+    //the node limit covers every target together, and no more
+    let iterative = SolveOptions {
+        iterative_deepening: true,
+        ..SolveOptions::default()
+    };
+    let tiles: Vec<char> = "quietly".chars().collect();
+    let unlimited = Solver::new(&tiles, &dictionary, iterative.clone()).solve();
+    let options = SolveOptions {
+        node_limit: Some(unlimited.stats.nodes),
+        ..iterative
+    };
+    let limited = Solver::new(&tiles, &dictionary, options).solve();
+    assert!(limited.optimal);
+    assert_eq!(limited.area, unlimited.area);
+    let cancel = Arc::new(AtomicBool::new(true));
+    let options = SolveOptions {
+        cancel: Some(cancel),
+        ..SolveOptions::default()
+    };
+    let cancelled = Solver::new(&tiles, &dictionary, options).solve();
+    assert!(!cancelled.optimal);
+    assert_eq!(cancelled.stats.nodes, 0);
+}
+
+#[test]
+fn resumes_from_checkpoint() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let tiles: Vec<char> = "loremipsum".chars().collect();
+    let full = Solver::new(&tiles, &dictionary, SolveOptions::default()).solve();
+    for &iterative_deepening in &[false, true] {
+        let options = SolveOptions {
+            iterative_deepening,
+            node_limit: Some(3000),
+            checkpoint_interval: Some(Duration::from_secs(3600)),
+            ..SolveOptions::default()
+        };
+        let saved = Mutex::new(None);
+        let stopped = Solver::new(&tiles, &dictionary, options).solve_with(&|event| {
+            if let SolveEvent::Checkpoint(checkpoint) = event {
+                let json = serde_json::to_string(checkpoint).unwrap();
+                *saved.lock().unwrap() = Some(json);
+            }
+        });
+        assert!(!stopped.optimal);
+        let json = saved.into_inner().unwrap().unwrap();
+        let checkpoint: Checkpoint = serde_json::from_str(&json).unwrap();
+        assert_eq!(checkpoint.target_area.is_some(), iterative_deepening);
+        let options = SolveOptions {
+            iterative_deepening,
+            ..SolveOptions::default()
+        };
+        let solver = Solver::new(&tiles, &dictionary, options.clone());
+        let first_words = Mutex::new(Vec::new());
+        let resumed = solver
+            .resume_with(&checkpoint, &|event| {
+                if let SolveEvent::FirstWord(word) = event {
+                    first_words.lock().unwrap().push(word.to_string());
+                }
+            })
+            .unwrap();
+        assert!(resumed.optimal);
+        assert_eq!(resumed.area, full.area);
+        //the first words already done aren't searched again
+        let first_words = first_words.into_inner().unwrap();
+        for &done in &checkpoint.completed_first_words {
+            assert!(!first_words.contains(&solver.dictionary().words()[done]));
+        }
+        let other: Vec<char> = "lorem".chars().collect();
+        assert_eq!(
+            Solver::new(&other, &dictionary, options)
+                .resume_with(&checkpoint, &|_| {})
+                .unwrap_err(),
+            CheckpointError::DifferentTiles {
+                saved: "loremipsum".to_string()
+            }
+        );
+    }
+}
+
+#[test]
+fn finds_every_optimal_board() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let tiles: Vec<char> = "lorem".chars().collect();
+    let all = |options: SolveOptions| {
+        let options = SolveOptions {
+            all_optimal: true,
+            ..options
+        };
+        Solver::new(&tiles, &dictionary, options).solve()
+    };
+    let solution = all(SolveOptions::default());
+    let area = Solver::new(&tiles, &dictionary, SolveOptions::default())
+        .solve()
+        .area;
+    assert_eq!(solution.area, area);
+    assert!(solution.optimal);
+    assert!(solution.optimal_count > 1);
+    assert_eq!(solution.optimal_boards.len(), solution.optimal_count);
+    let mut keys = HashSet::new();
+    for board in &solution.optimal_boards {
+        assert!(board.valid_bananagrams(&dictionary));
+        assert_eq!(Some(board.bounding_box_area()), area);
+        assert!(keys.insert(board.canonical_key()));
+    }
+    //the same boards however they are searched for
+    for options in [
+        SolveOptions {
+            threads: 4,
+            ..SolveOptions::default()
+        },
+        SolveOptions {
+            iterative_deepening: true,
+            ..SolveOptions::default()
+        },
+    ] {
+        let other = all(options);
+        let other_keys: HashSet<BoardKey> = other
+            .optimal_boards
+            .iter()
+            .map(Grid::canonical_key)
+            .collect();
+        assert_eq!(other_keys, keys);
+    }
+    //past the cap boards are only counted
+    let capped = all(SolveOptions {
+        max_optimal_boards: 1,
+        ..SolveOptions::default()
+    });
+    assert_eq!(capped.optimal_boards.len(), 1);
+    assert_eq!(capped.optimal_count, solution.optimal_count);
+}
+
+#[test]
+fn counts_every_board() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let tiles: Vec<char> = "lorem".chars().collect();
+    let count = Solver::new(&tiles, &dictionary, SolveOptions::default()).count();
+    assert!(count.complete);
+    assert_eq!(count.by_area.values().sum::<usize>(), count.total);
+    //the smallest boards are the ones all_optimal finds
+    let options = SolveOptions {
+        all_optimal: true,
+        ..SolveOptions::default()
+    };
+    let optimal = Solver::new(&tiles, &dictionary, options).solve();
+    let (&smallest, &boards) = count.by_area.iter().next().unwrap();
+    assert_eq!(Some(smallest), optimal.area);
+    assert_eq!(boards, optimal.optimal_count);
+    assert!(count.total > boards);
+    let options = SolveOptions {
+        threads: 4,
+        ..SolveOptions::default()
+    };
+    let threaded = Solver::new(&tiles, &dictionary, options).count();
+    assert_eq!(threaded.by_area, count.by_area);
+    //the rows generator misses boards, so it is swapped for the anchored one
+    for (hand, total) in [("lorem", 26), ("tread", 90)] {
+        let tiles: Vec<char> = hand.chars().collect();
+        for move_generator in [MoveGenerator::Rows, MoveGenerator::Anchored] {
+            let options = SolveOptions {
+                move_generator,
+                ..SolveOptions::default()
+            };
+            let count = Solver::new(&tiles, &dictionary, options).count();
+            assert_eq!(count.total, total, "{} {:?}", hand, move_generator);
+        }
+    }
+}
+
+#[test]
+fn finds_pareto_front() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let tiles: Vec<char> = "loremip".chars().collect();
+    let area = Solver::new(&tiles, &dictionary, SolveOptions::default())
+        .solve()
+        .area;
+    for measure in [ParetoMeasure::LongestSide, ParetoMeasure::Perimeter] {
+        let front = Solver::new(&tiles, &dictionary, SolveOptions::default()).pareto_front(measure);
+        assert!(front.complete);
+        assert_eq!(front.boards.first().map(|board| board.area), area);
+        for board in &front.boards {
+            assert!(board.grid.valid_bananagrams(&dictionary));
+            assert_eq!(board.area, board.grid.bounding_box_area());
+            assert_eq!(board.measure, measure.of(&board.grid.bounding_box()));
+        }
+        //each board is bigger than the last but better on the measure
+        for pair in front.boards.windows(2) {
+            assert!(pair[0].area < pair[1].area);
+            assert!(pair[0].measure > pair[1].measure);
+        }
+        let options = SolveOptions {
+            threads: 4,
+            ..SolveOptions::default()
+        };
+        let threaded = Solver::new(&tiles, &dictionary, options).pareto_front(measure);
+        let points = |front: &ParetoFront| -> Vec<(usize, usize)> {
+            front.boards.iter().map(|b| (b.area, b.measure)).collect()
+        };
+        assert_eq!(points(&threaded), points(&front));
+    }
+}
+
+#[test]
+fn objective_bounds_never_cut_the_best() {
+    use crate::objective::*;
+    //the same objective with no pruning at all
+    #[derive(Debug)]
+    struct Unbounded(Arc<dyn Objective>);
+    impl Objective for Unbounded {
+        fn score(&self, board: &Grid) -> Score {
+            self.0.score(board)
+        }
+        fn lower_bound(&self, board: &Grid, _: &[char]) -> Score {
+            vec![i64::MIN; self.score(board).len()]
+        }
+        fn name(&self) -> String {
+            self.0.name()
+        }
+    }
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let tiles: Vec<char> = "loremi".chars().collect();
+    let objectives: Vec<Arc<dyn Objective>> = vec![
+        Arc::new(Area),
+        Arc::new(Perimeter),
+        Arc::new(MostSquare),
+        Arc::new(FewestWords),
+        Arc::new(MostWords),
+        Arc::new(LongestWord),
+        Arc::new(Lexicographic(vec![Arc::new(MostSquare), Arc::new(Area)])),
+    ];
+    let area = Solver::new(&tiles, &dictionary, SolveOptions::default())
+        .solve()
+        .area;
+    for objective in objectives {
+        let best = |objective: Arc<dyn Objective>, threads: usize| {
+            let options = SolveOptions {
+                objective: Some(objective),
+                threads,
+                ..SolveOptions::default()
+            };
+            Solver::new(&tiles, &dictionary, options).solve()
+        };
+        let solution = best(objective.clone(), 1);
+        let grid = solution.grid.as_ref().unwrap();
+        assert!(grid.valid_bananagrams(&dictionary));
+        assert_eq!(solution.score, Some(objective.score(grid)));
+        let unbounded = best(Arc::new(Unbounded(objective.clone())), 1);
+        assert_eq!(solution.score, unbounded.score, "{}", objective.name());
+        assert_eq!(best(objective.clone(), 4).score, solution.score);
+        if objective.name() == "area" {
+            assert_eq!(solution.area, area);
+        }
+    }
+}
+
+#[test]
+fn places_as_many_tiles_as_possible() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let partial = |tiles: &str, threads: usize| {
+        let tiles: Vec<char> = tiles.chars().collect();
+        let options = SolveOptions {
+            partial: true,
+            threads,
+            ..SolveOptions::default()
+        };
+        Solver::new(&tiles, &dictionary, options).solve()
+    };
+    let full = Solver::new(
+        &"lorem".chars().collect::<Vec<_>>(),
+        &dictionary,
+        SolveOptions::default(),
+    )
+    .solve();
+    //no word has a q without a u
+    for threads in [1, 4] {
+        let solution = partial("loremq", threads);
+        let grid = solution.grid.unwrap();
+        assert!(grid.valid_bananagrams(&dictionary));
+        assert_eq!(solution.leftover_tiles, vec!['q']);
+        assert_eq!(solution.area, full.area);
+    }
+    //with every tile usable it is the usual minimum
+    let solution = partial("lorem", 1);
+    assert!(solution.leftover_tiles.is_empty());
+    assert_eq!(solution.area, full.area);
+    //with no word at all every tile is left over
+    let solution = partial("qzx", 1);
+    assert!(solution.grid.is_none());
+    assert!(solution.optimal);
+    assert_eq!(solution.leftover_tiles, vec!['q', 'z', 'x']);
+}
+
+#[test]
+fn other_searches_ignore_partial() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let solver = |tiles: &str| {
+        let tiles: Vec<char> = tiles.chars().collect();
+        let options = SolveOptions {
+            partial: true,
+            ..SolveOptions::default()
+        };
+        Solver::new(&tiles, &dictionary, options)
+    };
+    assert_eq!(solver("lorem").count().total, 26);
+    let loremipsum = solver("loremipsum");
+    let boxed = loremipsum.solve_in_box(3, 3);
+    assert!(boxed.grid.is_none());
+    assert!(boxed.leftover_tiles.is_empty());
+    let boxed = loremipsum.solve_in_box(5, 3);
+    assert_eq!(boxed.grid.unwrap().tile_count(), 10);
+    assert!(boxed.leftover_tiles.is_empty());
+    assert!(loremipsum.solve_within_area(11).grid.is_none());
+    //the q can't go anywhere, so no board uses every tile
+    let within = solver("loremq").solve_within_area(usize::MAX);
+    assert!(within.grid.is_none());
+    assert!(within.leftover_tiles.is_empty());
+}
+
+#[test]
+fn checkpoints_during_a_first_word() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let tiles: Vec<char> = "loremi".chars().collect();
+    let options = SolveOptions {
+        checkpoint_interval: Some(Duration::from_secs(0)),
+        ..SolveOptions::default()
+    };
+    let during = Mutex::new(false);
+    Solver::new(&tiles, &dictionary, options).solve_with(&|event| {
+        if let SolveEvent::Checkpoint(checkpoint) = event {
+            if checkpoint.best.is_some() && checkpoint.completed_first_words.is_empty() {
+                *during.lock().unwrap() = true;
+            }
+        }
+    });
+    //the first board was saved before the first word it was found under was done
+    assert!(during.into_inner().unwrap());
+}
+
+#[test]
+fn empty_hand_has_no_board() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    for iterative_deepening in [false, true] {
+        let options = SolveOptions {
+            iterative_deepening,
+            ..SolveOptions::default()
+        };
+        let solution = Solver::new(&[], &dictionary, options).solve();
+        assert!(solution.grid.is_none());
+        assert!(solution.optimal);
+    }
+    assert_eq!(rectangle_lower_bound(0, 0, 0), 0);
+}
+
+#[test]
+fn counts_candidates_by_line() {
+    let dictionary = Dictionary::from_file("common.txt").unwrap();
+    let tiles: Vec<char> = "lorem".chars().collect();
+    for move_generator in [MoveGenerator::Rows, MoveGenerator::Anchored] {
+        for timings in [false, true] {
+            let options = SolveOptions {
+                move_generator,
+                timings,
+                ..SolveOptions::default()
+            };
+            let stats = Solver::new(&tiles, &dictionary, options).solve().stats;
+            let by_row: u64 = stats.candidates_per_row.iter().sum();
+            let by_col: u64 = stats.candidates_per_col.iter().sum();
+            assert_eq!(by_row, stats.horizontal_candidates);
+            assert_eq!(by_col, stats.vertical_candidates);
+            assert!(by_row > 0 && by_col > 0);
+            assert_eq!(stats.timings.move_generation > Duration::ZERO, timings);
+        }
+    }
+}
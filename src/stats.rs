@@ -0,0 +1,127 @@
+//counters describing how a search went, for comparing options and heuristics
+use crate::grid::Direction;
+use std::{ops::AddAssign, time::Duration};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    //boards the search reached, including ones it cut off straight away
+    pub nodes: u64,
+    //the same split by depth, the first word being depth 1
+    pub nodes_per_depth: Vec<u64>,
+    //boards already searched from, according to the table
+    pub table_hits: u64,
+    //boards that couldn't beat the best area so far
+    pub bound_prunes: u64,
+    //boards that outgrew the rectangle given to solve_in_box
+    pub box_prunes: u64,
+    //boards with a word not in the dictionary, with preemptive_checking on
+    pub validity_prunes: u64,
+    //boards that used every tile, valid or not
+    pub complete_boards: u64,
+    //placements generated along rows and along columns
+    pub horizontal_candidates: u64,
+    pub vertical_candidates: u64,
+    //the same split by the row or column of the grid they were placed along
+    pub candidates_per_row: Vec<u64>,
+    pub candidates_per_col: Vec<u64>,
+    pub timings: PhaseTimings,
+}
+
+//time spent in each part of the search, summed over every thread; all but the index
+//are only timed if SolveOptions::timings asks for it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseTimings {
+    //building the trie or gaddag
+    pub index: Duration,
+    //canonical keys and table lookups
+    pub table: Duration,
+    pub bound: Duration,
+    //checking boards against the dictionary
+    pub validation: Duration,
+    pub move_generation: Duration,
+}
+
+impl SearchStats {
+    pub(crate) fn count_node(&mut self, depth: usize) {
+        self.nodes += 1;
+        add_at(&mut self.nodes_per_depth, depth, 1);
+    }
+
+    pub(crate) fn count_candidates(&mut self, dir: Direction, line: usize, candidates: u64) {
+        match dir {
+            Direction::Horizontal => {
+                self.horizontal_candidates += candidates;
+                add_at(&mut self.candidates_per_row, line, candidates);
+            }
+            Direction::Vertical => {
+                self.vertical_candidates += candidates;
+                add_at(&mut self.candidates_per_col, line, candidates);
+            }
+        }
+    }
+}
+
+fn add_at(counts: &mut Vec<u64>, i: usize, n: u64) {
+    if counts.len() <= i {
+        counts.resize(i + 1, 0);
+    }
+    counts[i] += n;
+}
+
+fn add_all(counts: &mut Vec<u64>, other: &[u64]) {
+    for (i, &n) in other.iter().enumerate() {
+        add_at(counts, i, n);
+    }
+}
+
+impl AddAssign for PhaseTimings {
+    fn add_assign(&mut self, other: PhaseTimings) {
+        self.index += other.index;
+        self.table += other.table;
+        self.bound += other.bound;
+        self.validation += other.validation;
+        self.move_generation += other.move_generation;
+    }
+}
+
+impl AddAssign<&SearchStats> for SearchStats {
+    fn add_assign(&mut self, other: &SearchStats) {
+        self.nodes += other.nodes;
+        add_all(&mut self.nodes_per_depth, &other.nodes_per_depth);
+        self.table_hits += other.table_hits;
+        self.bound_prunes += other.bound_prunes;
+        self.box_prunes += other.box_prunes;
+        self.validity_prunes += other.validity_prunes;
+        self.complete_boards += other.complete_boards;
+        self.horizontal_candidates += other.horizontal_candidates;
+        self.vertical_candidates += other.vertical_candidates;
+        add_all(&mut self.candidates_per_row, &other.candidates_per_row);
+        add_all(&mut self.candidates_per_col, &other.candidates_per_col);
+        self.timings += other.timings;
+    }
+}
+
+#[test]
+fn stats_add_up() {
+    let mut total = SearchStats::default();
+    let mut a = SearchStats::default();
+    a.count_node(1);
+    a.count_node(2);
+    a.bound_prunes = 1;
+    let mut b = SearchStats::default();
+    b.count_node(1);
+    b.count_node(3);
+    b.count_candidates(Direction::Horizontal, 2, 3);
+    b.count_candidates(Direction::Vertical, 0, 4);
+    a.count_candidates(Direction::Horizontal, 0, 1);
+    b.timings.table = Duration::from_millis(5);
+    total += &a;
+    total += &b;
+    assert_eq!(total.nodes, 4);
+    assert_eq!(total.nodes_per_depth, vec![0, 2, 1, 1]);
+    assert_eq!(total.bound_prunes, 1);
+    assert_eq!(total.candidates_per_row, vec![1, 0, 3]);
+    assert_eq!(total.candidates_per_col, vec![4]);
+    assert_eq!(total.horizontal_candidates, 4);
+    assert_eq!(total.timings.table, Duration::from_millis(5));
+}
@@ -0,0 +1,259 @@
+//fixed size record of the boards the search has already expanded.
+//forgetting a board only costs searching it again, so once the table is full
+//entries are replaced rather than letting memory grow with the search
+use crate::grid::BoardKey;
+use serde::Serialize;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    mem,
+    ops::AddAssign,
+    sync::Mutex,
+};
+
+const BUCKET_SIZE: usize = 4;
+//rough heap size of a key, which grows with the board's bounding box
+const KEY_BYTES: usize = 64;
+
+//which entry in a full bucket makes way for a new board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplacementPolicy {
+    //keep the boards nearest the root, whose subtrees are largest
+    DepthPreferred,
+    //keep the boards looked up most recently
+    LeastRecentlyUsed,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TableStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl AddAssign for TableStats {
+    fn add_assign(&mut self, other: TableStats) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.evictions += other.evictions;
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    key: BoardKey,
+    depth: usize,
+    last_used: u64,
+    //the earliest first word the board was searched under
+    first_word: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct TranspositionTable {
+    buckets: Vec<Vec<Entry>>,
+    policy: ReplacementPolicy,
+    clock: u64,
+    stats: TableStats,
+}
+
+impl TranspositionTable {
+    //sized to stay around memory_budget bytes when full
+    pub fn new(memory_budget: usize, policy: ReplacementPolicy) -> TranspositionTable {
+        let entry_size = mem::size_of::<Entry>() + KEY_BYTES;
+        let bucket_size = mem::size_of::<Vec<Entry>>() + BUCKET_SIZE * entry_size;
+        let buckets = (memory_budget / bucket_size).max(1);
+        TranspositionTable {
+            buckets: vec![Vec::new(); buckets],
+            policy,
+            clock: 0,
+            stats: TableStats::default(),
+        }
+    }
+
+    //the most boards the table will hold at once
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * BUCKET_SIZE
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.iter().map(|b| b.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.iter().all(|b| b.is_empty())
+    }
+
+    pub fn stats(&self) -> TableStats {
+        self.stats
+    }
+
+    //true if the board has been seen before, otherwise remember it
+    pub fn check_and_insert(&mut self, key: BoardKey, depth: usize) -> bool {
+        let hash = hash_key(&key);
+        self.check_and_insert_hashed(hash, key, depth, 0)
+    }
+
+    //a board seen only under later first words counts as unseen, so a search under
+    //an earlier one finds the same boards whatever order the first words ran in
+    fn check_and_insert_hashed(
+        &mut self,
+        hash: u64,
+        key: BoardKey,
+        depth: usize,
+        first_word: usize,
+    ) -> bool {
+        self.clock += 1;
+        let index = (hash % self.buckets.len() as u64) as usize;
+        let bucket = &mut self.buckets[index];
+        if let Some(entry) = bucket.iter_mut().find(|e| e.key == key) {
+            entry.last_used = self.clock;
+            entry.depth = entry.depth.min(depth);
+            if entry.first_word <= first_word {
+                self.stats.hits += 1;
+                return true;
+            }
+            entry.first_word = first_word;
+            self.stats.misses += 1;
+            return false;
+        }
+        self.stats.misses += 1;
+        let entry = Entry {
+            key,
+            depth,
+            last_used: self.clock,
+            first_word,
+        };
+        if bucket.len() < BUCKET_SIZE {
+            bucket.push(entry);
+            return false;
+        }
+        let victim = match self.policy {
+            ReplacementPolicy::DepthPreferred => {
+                let (i, deepest) = bucket
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, e)| (e.depth, u64::MAX - e.last_used))
+                    .unwrap();
+                if deepest.depth < depth {
+                    return false;
+                }
+                i
+            }
+            ReplacementPolicy::LeastRecentlyUsed => {
+                bucket
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, e)| e.last_used)
+                    .unwrap()
+                    .0
+            }
+        };
+        bucket[victim] = entry;
+        self.stats.evictions += 1;
+        false
+    }
+
+    pub fn clear(&mut self) {
+        for bucket in &mut self.buckets {
+            bucket.clear();
+        }
+    }
+}
+
+fn hash_key(key: &BoardKey) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+//a table split into independently locked shards so several threads can share it
+#[derive(Debug)]
+pub struct SharedTable {
+    shards: Vec<Mutex<TranspositionTable>>,
+}
+
+impl SharedTable {
+    //the memory budget is divided evenly between the shards
+    pub fn new(memory_budget: usize, policy: ReplacementPolicy, shards: usize) -> SharedTable {
+        let shards = shards.max(1);
+        SharedTable {
+            shards: (0..shards)
+                .map(|_| Mutex::new(TranspositionTable::new(memory_budget / shards, policy)))
+                .collect(),
+        }
+    }
+
+    //as TranspositionTable::check_and_insert, for a board searched under this first word
+    pub fn check_and_insert(&self, key: BoardKey, depth: usize, first_word: usize) -> bool {
+        let hash = hash_key(&key);
+        let shards = self.shards.len() as u64;
+        self.shards[(hash % shards) as usize]
+            .lock()
+            .unwrap()
+            .check_and_insert_hashed(hash / shards, key, depth, first_word)
+    }
+
+    pub fn stats(&self) -> TableStats {
+        let mut total = TableStats::default();
+        for shard in &self.shards {
+            total += shard.lock().unwrap().stats();
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+fn key_for(word: &str) -> BoardKey {
+    let mut grid = crate::grid::Grid::new(1, word.len());
+    for (i, letter) in word.chars().enumerate() {
+        grid.insert(0, i, letter);
+    }
+    grid.canonical_key()
+}
+
+#[test]
+fn table_stays_within_capacity() {
+    let mut table = TranspositionTable::new(4096, ReplacementPolicy::LeastRecentlyUsed);
+    let capacity = table.capacity();
+    assert!(capacity > 0 && capacity < 200);
+    let words: Vec<String> = (0..500).map(|i| format!("w{}", i)).collect();
+    for word in &words {
+        assert!(!table.check_and_insert(key_for(word), 1));
+    }
+    assert!(table.len() <= capacity);
+    let stats = table.stats();
+    assert_eq!(stats.misses, 500);
+    assert_eq!(stats.evictions as usize, 500 - table.len());
+    //the last board in is always still there
+    assert!(table.check_and_insert(key_for(&words[499]), 1));
+    assert_eq!(table.stats().hits, 1);
+}
+
+#[test]
+fn depth_preferred_keeps_shallow_boards() {
+    //a single bucket
+    let mut table = TranspositionTable::new(1, ReplacementPolicy::DepthPreferred);
+    assert_eq!(table.capacity(), BUCKET_SIZE);
+    for depth in 0..BUCKET_SIZE {
+        table.check_and_insert(key_for(&format!("s{}", depth)), depth);
+    }
+    //deeper than everything in the bucket, so it is not kept
+    assert!(!table.check_and_insert(key_for("deep"), 10));
+    assert!(!table.check_and_insert(key_for("deep"), 10));
+    //shallow enough to push out the deepest entry
+    assert!(!table.check_and_insert(key_for("shallow"), 0));
+    assert!(table.check_and_insert(key_for("shallow"), 0));
+    assert!(table.check_and_insert(key_for("s0"), 0));
+    assert!(!table.check_and_insert(key_for(&format!("s{}", BUCKET_SIZE - 1)), 9));
+}
+
+#[test]
+fn earlier_first_words_search_again() {
+    let table = SharedTable::new(1 << 16, ReplacementPolicy::DepthPreferred, 4);
+    assert!(!table.check_and_insert(key_for("hi"), 1, 3));
+    assert!(table.check_and_insert(key_for("hi"), 1, 3));
+    assert!(table.check_and_insert(key_for("hi"), 1, 5));
+    //seen only under a later first word
+    assert!(!table.check_and_insert(key_for("hi"), 1, 2));
+    assert!(table.check_and_insert(key_for("hi"), 1, 3));
+}
@@ -0,0 +1,218 @@
+//prefix tree over a word list, used to generate the words that fit in a row
+//instead of testing every word in the dictionary against it
+
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    //sorted by letter
+    children: Vec<(char, usize)>,
+    //index into the word list of the word ending here
+    word: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Trie {
+    nodes: Vec<TrieNode>,
+    words: Vec<String>,
+    longest: usize,
+}
+
+impl Trie {
+    pub fn new(words: &[String]) -> Trie {
+        let mut trie = Trie {
+            nodes: vec![TrieNode::default()],
+            words: Vec::new(),
+            longest: 0,
+        };
+        for word in words {
+            trie.insert(word);
+        }
+        trie
+    }
+
+    pub fn insert(&mut self, word: &str) {
+        let mut node = 0;
+        for letter in word.chars() {
+            node = match self.child(node, letter) {
+                Some(next) => next,
+                None => {
+                    let next = self.nodes.len();
+                    self.nodes.push(TrieNode::default());
+                    let children = &mut self.nodes[node].children;
+                    let index = children.partition_point(|(c, _)| *c < letter);
+                    children.insert(index, (letter, next));
+                    next
+                }
+            };
+        }
+        if self.nodes[node].word.is_none() {
+            self.nodes[node].word = Some(self.words.len());
+            self.words.push(word.to_string());
+            self.longest = self.longest.max(word.chars().count());
+        }
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        let mut node = 0;
+        for letter in word.chars() {
+            match self.child(node, letter) {
+                Some(next) => node = next,
+                None => return false,
+            }
+        }
+        self.nodes[node].word.is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    fn child(&self, node: usize, letter: char) -> Option<usize> {
+        let children = &self.nodes[node].children;
+        children
+            .binary_search_by_key(&letter, |(c, _)| *c)
+            .ok()
+            .map(|i| children[i].1)
+    }
+
+    //every word that can be laid along this row as a whole word of its own,
+    //filling the row's blanks from the rack and overlapping or sitting between
+    //the letters already there, in insertion order
+    pub fn words_fitting(&self, row: &str, rack: &[char]) -> Vec<String> {
+        let cells: Vec<char> = row.chars().collect();
+        let occupied: Vec<usize> = (0..cells.len()).filter(|&i| cells[i] != ' ').collect();
+        //the word has to reach into the span of letters already in the row
+        let (first, last) = match (occupied.first(), occupied.last()) {
+            (Some(&first), Some(&last)) => (first as isize, last as isize),
+            _ => (isize::MIN, isize::MAX),
+        };
+        let cell = |i: isize| -> char {
+            if i < 0 || i as usize >= cells.len() {
+                ' '
+            } else {
+                cells[i as usize]
+            }
+        };
+        let mut rack: Vec<(char, usize)> = {
+            let mut letters = rack.to_vec();
+            letters.sort_unstable();
+            let mut counts: Vec<(char, usize)> = Vec::new();
+            for letter in letters {
+                match counts.last_mut() {
+                    Some((c, n)) if *c == letter => *n += 1,
+                    _ => counts.push((letter, 1)),
+                }
+            }
+            counts
+        };
+
+        let mut found = vec![false; self.words.len()];
+        let longest = self.longest as isize;
+        let lowest_start = if first == isize::MIN {
+            -longest
+        } else {
+            first - longest + 1
+        };
+        let highest_start = if last == isize::MAX {
+            cells.len() as isize
+        } else {
+            last
+        };
+        for start in lowest_start..highest_start + 1 {
+            if cell(start - 1) != ' ' {
+                continue;
+            }
+            let mut walk = RowWalk {
+                trie: self,
+                cell: &cell,
+                span: (first, last),
+                start,
+                found: &mut found,
+            };
+            walk.extend(0, start, &mut rack);
+        }
+        (0..self.words.len())
+            .filter(|&i| found[i])
+            .map(|i| self.words[i].clone())
+            .collect()
+    }
+}
+
+struct RowWalk<'a, F: Fn(isize) -> char> {
+    trie: &'a Trie,
+    cell: &'a F,
+    span: (isize, isize),
+    start: isize,
+    found: &'a mut [bool],
+}
+
+impl<'a, F: Fn(isize) -> char> RowWalk<'a, F> {
+    //node is the trie node for the letters from start up to (not including) position
+    fn extend(&mut self, node: usize, position: isize, rack: &mut [(char, usize)]) {
+        let end = position - 1;
+        if let Some(word) = self.trie.nodes[node].word {
+            if (self.cell)(position) == ' ' && self.start <= self.span.1 && end >= self.span.0 {
+                self.found[word] = true;
+            }
+        }
+        match (self.cell)(position) {
+            ' ' => {
+                for i in 0..rack.len() {
+                    let (letter, count) = rack[i];
+                    if count == 0 {
+                        continue;
+                    }
+                    if let Some(next) = self.trie.child(node, letter) {
+                        rack[i].1 -= 1;
+                        self.extend(next, position + 1, rack);
+                        rack[i].1 += 1;
+                    }
+                }
+            }
+            letter => {
+                if let Some(next) = self.trie.child(node, letter) {
+                    self.extend(next, position + 1, rack);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn trie_contains() {
+    let words: Vec<String> = ["cat", "cats", "dog"]
+        .iter()
+        .map(|w| w.to_string())
+        .collect();
+    let trie = Trie::new(&words);
+    assert!(trie.contains("cat"));
+    assert!(trie.contains("cats"));
+    assert!(!trie.contains("ca"));
+    assert!(!trie.contains("dogs"));
+    assert_eq!(trie.len(), 3);
+}
+
+#[test]
+fn words_fitting_agrees_with_fits_in_row() {
+    use crate::grid::fits_in_row;
+    let words: Vec<String> = ["abc", "abcb", "cab", "abac", "aa", "aba", "a", "ca", "bab"]
+        .iter()
+        .map(|w| w.to_string())
+        .collect();
+    let trie = Trie::new(&words);
+    let tiles: Vec<char> = "ab".chars().collect();
+    for row in &["   a c ", "a a", "  b   ", "a    a"] {
+        let fitting = trie.words_fitting(row, &tiles);
+        for word in &fitting {
+            assert!(fits_in_row(word, &tiles, row), "{} in {:?}", word, row);
+        }
+    }
+    assert_eq!(
+        trie.words_fitting("   a c ", &tiles),
+        vec!["abc", "cab", "aa", "aba", "a", "ca"]
+    );
+    assert_eq!(trie.words_fitting("a a", &tiles), vec!["aa", "aba", "a"]);
+}
@@ -0,0 +1,145 @@
+//what is wrong with a board, for explaining a hand built board to whoever built it
+use crate::dictionary::Dictionary;
+use crate::grid::{Direction, Grid, PlacedWord};
+use std::fmt;
+
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    //runs of letters that aren't in the dictionary
+    pub invalid_words: Vec<PlacedWord>,
+    //the cells of each group of tiles, when there is more than one
+    pub islands: Vec<Vec<(usize, usize)>>,
+    //tiles in the hand that aren't on the board
+    pub unused_tiles: Vec<char>,
+    //tiles on the board that aren't in the hand
+    pub excess_tiles: Vec<char>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.invalid_words.is_empty()
+            && self.islands.len() <= 1
+            && self.unused_tiles.is_empty()
+            && self.excess_tiles.is_empty()
+    }
+}
+
+impl Grid {
+    //everything valid_bananagrams checks, plus whether the board uses exactly
+    //the tiles in the hand if one is given
+    pub fn validate(&self, dictionary: &Dictionary, hand: Option<&[char]>) -> ValidationReport {
+        let mut report = ValidationReport {
+            invalid_words: self
+                .placed_words()
+                .into_iter()
+                .filter(|placed| !dictionary.contains(&placed.word))
+                .collect(),
+            ..ValidationReport::default()
+        };
+        let islands = self.islands();
+        if islands.len() > 1 {
+            report.islands = islands;
+        }
+        if let Some(hand) = hand {
+            let mut unused = hand.to_vec();
+            let (rows, cols) = self.dim();
+            for row in 0..rows {
+                for col in 0..cols {
+                    let letter = self.get(row, col);
+                    if letter == ' ' {
+                        continue;
+                    }
+                    match unused.iter().position(|x| *x == letter) {
+                        Some(index) => {
+                            unused.remove(index);
+                        }
+                        None => report.excess_tiles.push(letter),
+                    }
+                }
+            }
+            report.unused_tiles = unused;
+        }
+        report
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_valid() {
+            return writeln!(f, "Valid board");
+        }
+        for placed in &self.invalid_words {
+            let direction = match placed.direction {
+                Direction::Horizontal => "across",
+                Direction::Vertical => "down",
+            };
+            writeln!(
+                f,
+                "'{}' at row {}, column {} ({}) is not a word",
+                placed.word, placed.row, placed.col, direction
+            )?;
+        }
+        if self.islands.len() > 1 {
+            writeln!(f, "Board is in {} separate pieces:", self.islands.len())?;
+            for island in &self.islands {
+                let (row, col) = island.iter().min().unwrap();
+                let plural = if island.len() == 1 { "" } else { "s" };
+                writeln!(
+                    f,
+                    "    {} tile{} starting at row {}, column {}",
+                    island.len(),
+                    plural,
+                    row,
+                    col
+                )?;
+            }
+        }
+        if !self.unused_tiles.is_empty() {
+            let tiles: String = self.unused_tiles.iter().collect();
+            writeln!(f, "Tiles left over: {}", tiles)?;
+        }
+        if !self.excess_tiles.is_empty() {
+            let tiles: String = self.excess_tiles.iter().collect();
+            writeln!(f, "Tiles not in the hand: {}", tiles)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn reports_each_problem() {
+    let dictionary = Dictionary::from_slice(&["hi", "hey", "yo"]);
+    let board = Grid::from_rows(&["hi  ", "ex  ", "y  o", "   q"]);
+    let report = board.validate(&dictionary, Some(&"heyyox".chars().collect::<Vec<char>>()));
+    assert!(!report.is_valid());
+    let invalid: Vec<(&str, usize, usize, Direction)> = report
+        .invalid_words
+        .iter()
+        .map(|p| (p.word.as_str(), p.row, p.col, p.direction))
+        .collect();
+    assert_eq!(
+        invalid,
+        vec![
+            ("ex", 1, 0, Direction::Horizontal),
+            ("ix", 0, 1, Direction::Vertical),
+            ("oq", 2, 3, Direction::Vertical)
+        ]
+    );
+    assert_eq!(report.islands.len(), 2);
+    assert_eq!(report.unused_tiles, vec!['y']);
+    assert_eq!(report.excess_tiles, vec!['i', 'q']);
+    let text = report.to_string();
+    assert!(text.contains("'ix' at row 0, column 1 (down) is not a word"));
+    assert!(text.contains("Board is in 2 separate pieces"));
+}
+
+#[test]
+fn valid_board_reports_nothing() {
+    let dictionary = Dictionary::from_slice(&["hi", "hey", "yo"]);
+    let board = Grid::from_rows(&["hi", "e ", "yo"]);
+    let report = board.validate(&dictionary, Some(&"hiheyo".chars().collect::<Vec<char>>()));
+    assert!(!report.is_valid());
+    let report = board.validate(&dictionary, Some(&"hieyo".chars().collect::<Vec<char>>()));
+    assert!(report.is_valid(), "{}", report);
+    assert!(board.valid_bananagrams(&dictionary));
+}